@@ -111,17 +111,11 @@ fn main() {
 
     let query = format!("select {} from table_name where {}", QueryRow::generate_select_clause().join(", "), data.generate_where_clause().join(" AND "));
 
+    // 参数与占位符的顺序一致，不需要再手动判断字段是否为 None
+    let (_, args) = data.generate_where_clause_with_args();
     // let mut sql = sqlx::query::<_, QueryRow>(query);
-    // if data.keyword.is_some() {
-    //     sql = sql.bind(data.keyword);
-    // }
-    // if data.start_time.is_some() {
-    //     sql = sql.bind(data.start_time);
-    // }
-    // if data.end_time.is_some() {
-    //     sql = sql.bind(data.end_time);
-    // }
-    // let result = sql.bind(ty).fetch_all(pool).await?;
+    // for arg in args { ... sql = sql.bind(...) }
+    println!("{:?}", args);
 
     println!("{}", query);
 
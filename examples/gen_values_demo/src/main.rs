@@ -6,7 +6,6 @@ struct PostgresStruct {
     field1: i32,
     #[value(ignore)]
     field2: i32,
-    #[value(index = 4)]
     field3: i32,
 }
 
@@ -31,6 +30,6 @@ struct PostgresSetIndexStruct {
 
 fn main() {
     println!("MysqlStruct: {:?}", MysqlStruct::generate_values_clause()); // 输出：["?", "?"]
-    println!("{:?}", PostgresStruct::generate_values_clause()); // 输出：["$1", "$4"]
+    println!("{:?}", PostgresStruct::generate_values_clause()); // 输出：["$1", "$2"]
     println!("{:?}", PostgresSetIndexStruct::generate_values_clause()); // 输出：["$5", "$6"]
 }
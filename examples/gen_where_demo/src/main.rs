@@ -71,6 +71,6 @@ fn main() {
         field8: 0,
         field10: None,
     };
-    // 输出 ["field4 < $1", "field5 not null", "rename_filed >= $3", "field7 = ANY($4::int[])", "rename_filed2 = ANY($5::int[])", "field10 = $6"]
+    // 输出 ["field4 < $1", "field5 not null", "rename_filed >= $2", "field7 = ANY($3::int[])", "rename_filed2 = ANY($4::int[])", "field10 = $5"]
     println!("{:?}", data.generate_where_clause());
 }
@@ -0,0 +1,49 @@
+use std::any::Any;
+use std::fmt;
+
+/// `SqlArg` 表示生成的 SQL 片段中，占位符所对应的绑定参数。
+///
+/// 派生宏生成的 `*_with_args` 方法会按照占位符出现的顺序返回 `SqlArg` 列表，
+/// 调用方只需要按顺序绑定即可，不需要再手动判断哪些字段被忽略。
+///
+/// `SqlArg` 不依赖任何数据库驱动，可以通过 `downcast_ref` 取回原始类型后再交给驱动绑定。
+pub enum SqlArg<'a> {
+    /// 对结构体字段的引用
+    Ref(&'a dyn Any),
+    /// 由字段生成的新值，如 `#[r#where(like = "...")]` 转义并加上 `%` 之后的 `String`
+    Owned(Box<dyn Any>),
+}
+
+impl<'a> SqlArg<'a> {
+    /// 返回参数值的 `&dyn Any` 引用
+    pub fn as_any(&self) -> &dyn Any {
+        match self {
+            SqlArg::Ref(value) => *value,
+            SqlArg::Owned(value) => value.as_ref(),
+        }
+    }
+
+    /// 尝试将参数值转换为具体类型 `T` 的引用
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::SqlArg;
+    ///
+    /// let title = Some("这是标题".to_string());
+    /// let arg = SqlArg::Ref(&title);
+    /// assert_eq!(arg.downcast_ref::<Option<String>>(), Some(&title));
+    /// assert_eq!(arg.downcast_ref::<i32>(), None);
+    /// ```
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+}
+
+impl<'a> fmt::Debug for SqlArg<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlArg::Ref(_) => f.write_str("SqlArg::Ref(..)"),
+            SqlArg::Owned(_) => f.write_str("SqlArg::Owned(..)"),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::marker::PhantomData;
+
+use crate::{Dialect, SelectAttributeMacro, SqlArg, SqlToolError, WhereAttributeMacro};
+
+/// `SelectBuilder` 用于组合 `SelectAttributeMacro` 和 `WhereAttributeMacro` 生成完整的 `SELECT` 语句。
+///
+/// 查询的字段和表名来自行类型 `T`（`#[derive(GenSelect)]` 和 `#[config(table = "...")]`），
+/// 条件来自可选的过滤结构体（`#[derive(GenWhere)]`）。行类型设置了 `#[config(alias = "...")]` 时，
+/// 会生成 `FROM table alias`。当过滤结构体没有任何生效的条件时，
+/// 生成的语句不会包含 `WHERE` 关键字。
+///
+/// # 示例
+/// ```ignore
+/// use sql_tool_kit::*;
+///
+/// #[derive(GenSelect)]
+/// #[config(table = "advert")]
+/// pub struct QueryRow {
+///     pub title: String,
+///     #[select(rename = "type")]
+///     pub ty: i32,
+/// }
+///
+/// #[derive(GenWhere)]
+/// #[config(database = "postgres")]
+/// pub struct QueryForm {
+///     #[r#where(condition = ">=")]
+///     pub start_time: Option<String>,
+/// }
+///
+/// let form = QueryForm { start_time: Some("2024/12/12".to_string()) };
+/// // 输出：SELECT title, type FROM advert WHERE start_time >= $1
+/// let sql = SelectBuilder::<QueryRow>::new().filter(&form).build()?;
+/// // 输出：SELECT title, type FROM advert
+/// let sql = SelectBuilder::<QueryRow>::new().build()?;
+/// // 输出：SELECT title, type FROM advert WHERE start_time >= ?
+/// let sql = SelectBuilder::<QueryRow>::new().filter(&form).dialect(Dialect::Sqlite).build()?;
+/// ```
+pub struct SelectBuilder<'a, T> {
+    filter: Option<&'a dyn WhereAttributeMacro>,
+    dialect: Option<Dialect>,
+    _row: PhantomData<T>,
+}
+
+impl<'a, T: SelectAttributeMacro> SelectBuilder<'a, T> {
+    /// 创建一个没有条件的 `SelectBuilder`
+    pub fn new() -> Self {
+        SelectBuilder {
+            filter: None,
+            dialect: None,
+            _row: PhantomData,
+        }
+    }
+
+    /// 设置过滤条件
+    pub fn filter<W: WhereAttributeMacro>(mut self, filter: &'a W) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// 设置渲染占位符和引号使用的数据库，未设置时使用结构体上的 `#[config(database = "...")]`
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// 生成完整的 `SELECT` 语句
+    ///
+    /// # Errors
+    /// 行类型上没有设置 `#[config(table = "...")]` 时返回 `SqlToolError::MissingTable`
+    pub fn build(&self) -> Result<String, SqlToolError> {
+        self.build_with_args().map(|(sql, _)| sql)
+    }
+
+    /// 与 `build` 相同，同时返回按占位符顺序排列的参数列表
+    pub fn build_with_args(&self) -> Result<(String, Vec<SqlArg<'a>>), SqlToolError> {
+        let table = T::table_name().ok_or(SqlToolError::MissingTable)?;
+        let select = match self.dialect {
+            Some(dialect) => T::generate_select_clause_for(dialect),
+            None => T::generate_select_clause(),
+        };
+        let mut sql = format!("SELECT {} FROM {}", select.join(", "), table);
+        if let Some(alias) = T::table_alias() {
+            sql.push(' ');
+            sql.push_str(alias);
+        }
+        let (fields, args) = match (self.filter, self.dialect) {
+            (Some(filter), Some(dialect)) => filter.generate_where_clause_with_args_for(dialect),
+            (Some(filter), None) => filter.generate_where_clause_with_args(),
+            (None, _) => (Vec::new(), Vec::new()),
+        };
+        if !fields.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&fields.join(" AND "));
+        }
+        Ok((sql, args))
+    }
+}
+
+impl<'a, T: SelectAttributeMacro> Default for SelectBuilder<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
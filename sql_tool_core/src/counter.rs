@@ -0,0 +1,74 @@
+use crate::Dialect;
+
+/// `ParamCounter` 用于在一条语句的多个部分之间共享占位符序号。
+///
+/// 一条语句由多个派生结构体组成时（如 `SET` 来自 `GenSet`，`WHERE` 来自 `GenWhere`），
+/// 将同一个 `ParamCounter` 依次传给各个 `*_with_counter` 方法，生成的占位符会组成连续的 `$1..$n`，
+/// 最后可以通过 `bind_count` 得到需要绑定的参数个数。
+///
+/// # 示例
+/// ```ignore
+/// use sql_tool_kit::*;
+///
+/// let mut counter = ParamCounter::new(Dialect::Postgres);
+/// // 输出：["title = $1", "description = $2"]
+/// let set_values = update_form.generate_set_clause_with_counter(&mut counter);
+/// // 输出：["id = $3"]
+/// let where_values = where_form.generate_where_clause_with_counter(&mut counter);
+/// assert_eq!(counter.next_index(), 4);
+/// assert_eq!(counter.bind_count(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParamCounter {
+    dialect: Dialect,
+    start: usize,
+    next: usize,
+    positional: usize,
+}
+
+impl ParamCounter {
+    /// 创建一个从 `1` 开始的计数器
+    pub fn new(dialect: Dialect) -> Self {
+        Self::with_start(dialect, 1)
+    }
+
+    /// 创建一个从 `start` 开始的计数器
+    pub fn with_start(dialect: Dialect, start: usize) -> Self {
+        ParamCounter {
+            dialect,
+            start,
+            next: start,
+            positional: 0,
+        }
+    }
+
+    /// 渲染占位符使用的数据库
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// 下一个可用的占位符序号
+    pub fn next_index(&self) -> usize {
+        self.next
+    }
+
+    /// 目前为止生成的占位符需要绑定的参数个数。
+    ///
+    /// 带序号的占位符（如 `$1`）为序号的个数，重复引用的序号只需要绑定一次；
+    /// 使用 `?` 的数据库则为占位符出现的次数。
+    pub fn bind_count(&self) -> usize {
+        if self.dialect.numbered_placeholders() {
+            self.next - self.start
+        } else {
+            self.positional
+        }
+    }
+
+    /// 记录一次生成的结果，由派生宏生成的代码调用。
+    ///
+    /// `next_index` 为生成之后下一个可用的序号，`positional` 为使用 `?` 的数据库需要绑定的参数个数。
+    pub fn advance(&mut self, next_index: usize, positional: usize) {
+        self.next = self.next.max(next_index);
+        self.positional += positional;
+    }
+}
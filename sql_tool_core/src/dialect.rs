@@ -0,0 +1,169 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::StatementKind;
+
+/// `Dialect` 表示生成 SQL 时使用的数据库方言。
+///
+/// 派生宏中 `#[config(database = "...")]` 的值会被解析为对应的 `Dialect`，
+/// 生成的代码在运行时通过 `Dialect` 渲染占位符，因此同一个结构体也可以在运行时选择不同的数据库。
+///
+/// 支持的名称：`postgres`、`mysql`、`mariadb`、`sqlite`、`mssql`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// PostgreSQL，占位符为 `$1`, `$2`, ...
+    Postgres,
+    /// MySQL，占位符为 `?`
+    MySql,
+    /// MariaDB，占位符为 `?`
+    MariaDb,
+    /// SQLite，占位符为 `?`
+    Sqlite,
+    /// Microsoft SQL Server，占位符为 `@p1`, `@p2`, ...
+    MsSql,
+}
+
+impl Dialect {
+    /// 所有支持的数据库方言
+    pub const ALL: [Dialect; 5] = [
+        Dialect::Postgres,
+        Dialect::MySql,
+        Dialect::MariaDb,
+        Dialect::Sqlite,
+        Dialect::MsSql,
+    ];
+
+    /// 根据 `#[config(database = "...")]` 中使用的名称返回对应的方言
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::Dialect;
+    ///
+    /// assert_eq!(Dialect::from_name("postgres"), Some(Dialect::Postgres));
+    /// assert_eq!(Dialect::from_name("oracle"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Dialect> {
+        match name {
+            "postgres" => Some(Dialect::Postgres),
+            "mysql" => Some(Dialect::MySql),
+            "mariadb" => Some(Dialect::MariaDb),
+            "sqlite" => Some(Dialect::Sqlite),
+            "mssql" => Some(Dialect::MsSql),
+            _ => None,
+        }
+    }
+
+    /// 返回方言在 `#[config(database = "...")]` 中使用的名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dialect::Postgres => "postgres",
+            Dialect::MySql => "mysql",
+            Dialect::MariaDb => "mariadb",
+            Dialect::Sqlite => "sqlite",
+            Dialect::MsSql => "mssql",
+        }
+    }
+
+    /// 渲染第 `index` 个参数的占位符
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::Dialect;
+    ///
+    /// assert_eq!(Dialect::Postgres.placeholder(1), "$1");
+    /// assert_eq!(Dialect::MySql.placeholder(1), "?");
+    /// assert_eq!(Dialect::MsSql.placeholder(2), "@p2");
+    /// ```
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            Dialect::Postgres => format!("${}", index),
+            Dialect::MySql | Dialect::MariaDb | Dialect::Sqlite => "?".to_string(),
+            Dialect::MsSql => format!("@p{}", index),
+        }
+    }
+
+    /// 使用方言的引号包裹标识符，并转义标识符中的引号
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::Dialect;
+    ///
+    /// assert_eq!(Dialect::Postgres.quote_identifier("type"), "\"type\"");
+    /// assert_eq!(Dialect::MySql.quote_identifier("a`b"), "`a``b`");
+    /// assert_eq!(Dialect::MsSql.quote_identifier("order"), "[order]");
+    /// ```
+    pub fn quote_identifier(&self, name: &str) -> String {
+        match self {
+            Dialect::Postgres | Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+            Dialect::MySql | Dialect::MariaDb => format!("`{}`", name.replace('`', "``")),
+            Dialect::MsSql => format!("[{}]", name.replace(']', "]]")),
+        }
+    }
+
+    /// 占位符是否带有序号（如 `$1`），带序号的占位符可以在语句中重复引用同一个参数
+    pub fn numbered_placeholders(&self) -> bool {
+        matches!(self, Dialect::Postgres | Dialect::MsSql)
+    }
+
+    /// 是否支持在 `kind` 语句中返回数据（`RETURNING` 或 MsSql 的 `OUTPUT`）
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::{Dialect, StatementKind};
+    ///
+    /// assert!(Dialect::Postgres.supports_returning(StatementKind::Update));
+    /// assert!(Dialect::MariaDb.supports_returning(StatementKind::Insert));
+    /// assert!(!Dialect::MariaDb.supports_returning(StatementKind::Update));
+    /// assert!(!Dialect::MySql.supports_returning(StatementKind::Delete));
+    /// ```
+    pub fn supports_returning(&self, kind: StatementKind) -> bool {
+        match self {
+            Dialect::Postgres | Dialect::Sqlite | Dialect::MsSql => true,
+            Dialect::MariaDb => kind != StatementKind::Update,
+            Dialect::MySql => false,
+        }
+    }
+
+    /// 单条语句中允许绑定的最大参数个数
+    ///
+    /// MsSql 每次请求最多 2100 个参数，但驱动通过 `sp_executesql` 执行带参数的语句时，
+    /// 语句本身和参数定义会占用其中 2 个，实际可以绑定的参数为 2098 个。
+    pub fn max_parameters(&self) -> usize {
+        match self {
+            Dialect::Postgres => 65535,
+            Dialect::MySql | Dialect::MariaDb => 65535,
+            Dialect::Sqlite => 32766,
+            Dialect::MsSql => 2098,
+        }
+    }
+
+    /// 单条 `INSERT ... VALUES` 语句中允许插入的最大行数，没有限制时为 `None`
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::Dialect;
+    ///
+    /// assert_eq!(Dialect::MsSql.max_rows(), Some(1000));
+    /// assert_eq!(Dialect::Postgres.max_rows(), None);
+    /// ```
+    pub fn max_rows(&self) -> Option<usize> {
+        match self {
+            Dialect::MsSql => Some(1000),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Dialect::from_name(s).ok_or_else(|| format!("未支持的数据库类型 `{}`", s))
+    }
+}
@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Dialect, StatementKind};
+
+/// 生成完整 SQL 语句时可能出现的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlToolError {
+    /// 结构体上没有设置 `#[config(table = "...")]`
+    MissingTable,
+    /// `UPDATE` 语句中没有任何需要更新的字段
+    EmptySet,
+    /// 语句中没有任何 `WHERE` 条件，且没有显式允许作用于整张表
+    MissingWhere,
+    /// 生成 upsert 语句时没有设置 `#[config(conflict = [...])]`
+    MissingConflict,
+    /// 数据库不支持在该语句中使用 `RETURNING`/`OUTPUT` 返回数据
+    UnsupportedReturning(Dialect, StatementKind),
+}
+
+impl fmt::Display for SqlToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlToolError::MissingTable => {
+                f.write_str("没有设置表名，请在结构上设置 `#[config(table = \"...\")]`")
+            }
+            SqlToolError::EmptySet => f.write_str("UPDATE 语句中没有需要更新的字段"),
+            SqlToolError::MissingWhere => {
+                f.write_str("语句中没有任何 WHERE 条件，这会作用于整张表")
+            }
+            SqlToolError::MissingConflict => {
+                f.write_str("没有设置冲突字段，请在结构上设置 `#[config(conflict = [\"...\"])]`")
+            }
+            SqlToolError::UnsupportedReturning(dialect, kind) => {
+                write!(f, "{} 不支持在 {} 语句中返回数据", dialect, kind)
+            }
+        }
+    }
+}
+
+impl Error for SqlToolError {}
@@ -0,0 +1,290 @@
+use std::fmt;
+
+/// `SqlFragment` 表示派生宏生成的一段 SQL，以及这段 SQL 的来源信息。
+///
+/// 除了文本之外还记录了：
+/// - 生成该片段的结构体字段
+/// - 片段中的占位符序号（片段需要绑定参数时）
+/// - 片段是否包含 `rename`、`value`、`condition_all` 等自定义的 SQL 表达式
+///
+/// # 示例
+/// ```
+/// use sql_tool_core::SqlFragment;
+///
+/// let fragment = SqlFragment::new("title = $1", "title").with_index(1);
+/// assert_eq!(fragment.to_string(), "title = $1");
+/// assert_eq!(fragment.field(), "title");
+/// assert!(fragment.binds_param());
+/// assert!(!fragment.is_expression());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlFragment {
+    text: String,
+    field: &'static str,
+    index: Option<usize>,
+    expression: bool,
+}
+
+impl SqlFragment {
+    /// 创建一个由字段 `field` 生成的片段
+    pub fn new(text: impl Into<String>, field: &'static str) -> Self {
+        SqlFragment {
+            text: text.into(),
+            field,
+            index: None,
+            expression: false,
+        }
+    }
+
+    /// 设置片段中占位符的序号，表示该片段需要绑定参数
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// 标记该片段包含自定义的 SQL 表达式
+    pub fn with_expression(mut self) -> Self {
+        self.expression = true;
+        self
+    }
+
+    /// 片段的 SQL 文本
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 生成该片段的结构体字段名称，`Clause::group` 合并的片段为分组的名称
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    /// 片段中占位符的序号，不需要绑定参数时返回 `None`。
+    ///
+    /// 使用 `?` 的数据库同样会记录序号，表示该参数在语句中的位置。
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// 片段是否需要绑定参数
+    pub fn binds_param(&self) -> bool {
+        self.index.is_some()
+    }
+
+    /// 片段是否包含 `rename`、`value`、`condition_all` 等自定义的 SQL 表达式
+    pub fn is_expression(&self) -> bool {
+        self.expression
+    }
+
+    /// 返回片段的 SQL 文本
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+impl fmt::Display for SqlFragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<SqlFragment> for String {
+    fn from(fragment: SqlFragment) -> Self {
+        fragment.text
+    }
+}
+
+/// `Clause` 是一组按顺序排列的 `SqlFragment`，如 `SET` 或 `WHERE` 部分的所有条件。
+///
+/// # 示例
+/// ```
+/// use sql_tool_core::{Clause, SqlFragment};
+///
+/// let clause: Clause = vec![
+///     SqlFragment::new("id = $1", "id").with_index(1),
+///     SqlFragment::new("deleted_at IS NULL", "deleted_at").with_expression(),
+/// ]
+/// .into_iter()
+/// .collect();
+/// assert_eq!(clause.join(" AND "), "id = $1 AND deleted_at IS NULL");
+/// assert_eq!(clause.bind_count(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Clause {
+    fragments: Vec<SqlFragment>,
+}
+
+impl Clause {
+    /// 创建一个空的 `Clause`
+    pub fn new() -> Self {
+        Clause::default()
+    }
+
+    /// 在末尾添加一个片段
+    pub fn push(&mut self, fragment: SqlFragment) {
+        self.fragments.push(fragment);
+    }
+
+    /// 片段的个数
+    pub fn len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// 是否没有任何片段
+    pub fn is_empty(&self) -> bool {
+        self.fragments.is_empty()
+    }
+
+    /// 按顺序遍历所有片段
+    pub fn iter(&self) -> std::slice::Iter<'_, SqlFragment> {
+        self.fragments.iter()
+    }
+
+    /// 所有片段
+    pub fn fragments(&self) -> &[SqlFragment] {
+        &self.fragments
+    }
+
+    /// 需要绑定参数的片段个数
+    pub fn bind_count(&self) -> usize {
+        self.iter()
+            .filter(|fragment| fragment.binds_param())
+            .count()
+    }
+
+    /// 使用 `separator` 拼接所有片段，如 `", "` 或 `" AND "`
+    pub fn join(&self, separator: &str) -> String {
+        self.iter()
+            .map(SqlFragment::text)
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// 使用 `operator`（`AND` 或 `OR`）将所有片段合并为一个带括号的片段，`field` 为分组的名称。
+    ///
+    /// 没有任何片段时返回 `None`，只有一个片段时直接返回该片段。
+    /// 合并后的片段被标记为表达式，占位符序号为其中最小的序号。
+    /// 被标记为表达式的片段（如 `condition_all`、其他分组）可能包含 `AND`、`OR`，
+    /// 合并时会再使用括号包裹，已经被一对括号完整包裹的片段除外。
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::{Clause, SqlFragment};
+    ///
+    /// let clause: Clause = vec![
+    ///     SqlFragment::new("title LIKE $1", "title").with_index(1),
+    ///     SqlFragment::new("subtitle LIKE $2", "subtitle").with_index(2),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    /// let fragment = clause.group("OR", "keyword").unwrap();
+    /// assert_eq!(fragment.text(), "(title LIKE $1 OR subtitle LIKE $2)");
+    /// assert_eq!(fragment.field(), "keyword");
+    /// assert_eq!(fragment.index(), Some(1));
+    /// assert!(Clause::new().group("OR", "keyword").is_none());
+    ///
+    /// let clause: Clause = vec![
+    ///     SqlFragment::new("a = 1 OR b = 2", "a").with_expression(),
+    ///     fragment,
+    ///     SqlFragment::new("c = $3", "c").with_index(3),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    /// assert_eq!(
+    ///     clause.group("AND", "filter").unwrap().text(),
+    ///     "((a = 1 OR b = 2) AND (title LIKE $1 OR subtitle LIKE $2) AND c = $3)"
+    /// );
+    /// ```
+    pub fn group(self, operator: &str, field: &'static str) -> Option<SqlFragment> {
+        if self.fragments.len() <= 1 {
+            return self.fragments.into_iter().next();
+        }
+        let index = self.iter().filter_map(SqlFragment::index).min();
+        let text = self
+            .iter()
+            .map(|fragment| {
+                if fragment.is_expression() && !is_parenthesized(fragment.text()) {
+                    format!("({})", fragment.text())
+                } else {
+                    fragment.text().to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(&format!(" {} ", operator));
+        let text = format!("({})", text);
+        Some(SqlFragment {
+            text,
+            field,
+            index,
+            expression: true,
+        })
+    }
+
+    /// 返回所有片段的 SQL 文本
+    pub fn into_strings(self) -> Vec<String> {
+        self.fragments
+            .into_iter()
+            .map(SqlFragment::into_string)
+            .collect()
+    }
+}
+
+/// `text` 是否被一对括号完整包裹，如 `(a OR b)`，`(a) OR (b)` 不是
+fn is_parenthesized(text: &str) -> bool {
+    if !text.starts_with('(') || !text.ends_with(')') {
+        return false;
+    }
+    let mut depth = 0usize;
+    let mut quoted = false;
+    for (position, char) in text.char_indices() {
+        match char {
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return position == text.len() - 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+impl FromIterator<SqlFragment> for Clause {
+    fn from_iter<I: IntoIterator<Item = SqlFragment>>(iter: I) -> Self {
+        Clause {
+            fragments: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<SqlFragment> for Clause {
+    fn extend<I: IntoIterator<Item = SqlFragment>>(&mut self, iter: I) {
+        self.fragments.extend(iter);
+    }
+}
+
+impl IntoIterator for Clause {
+    type Item = SqlFragment;
+    type IntoIter = std::vec::IntoIter<SqlFragment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fragments.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Clause {
+    type Item = &'a SqlFragment;
+    type IntoIter = std::slice::Iter<'a, SqlFragment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<Clause> for Vec<String> {
+    fn from(clause: Clause) -> Self {
+        clause.into_strings()
+    }
+}
@@ -1,3 +1,39 @@
+mod arg;
+mod builder;
+mod counter;
+mod dialect;
+mod error;
+mod fragment;
+mod like;
+mod plan;
+#[cfg(feature = "postgres")]
+mod postgres_params;
+mod range;
+mod returning;
+#[cfg(feature = "rusqlite")]
+mod rusqlite_params;
+#[cfg(feature = "sqlx")]
+mod sqlx_args;
+mod upsert;
+
+pub use arg::SqlArg;
+pub use builder::SelectBuilder;
+pub use counter::ParamCounter;
+pub use dialect::Dialect;
+pub use error::SqlToolError;
+pub use fragment::{Clause, SqlFragment};
+pub use like::{LikeMode, LikeParam, LikeValue};
+pub use plan::{BindPlan, SlotMap};
+#[cfg(feature = "postgres")]
+pub use postgres_params::{PgParam, PgSetParams, PgValuesParams, PgWhereParams};
+pub use range::RangeValue;
+pub use returning::StatementKind;
+#[cfg(feature = "rusqlite")]
+pub use rusqlite_params::{RusqliteSetParams, RusqliteValuesParams, RusqliteWhereParams};
+#[cfg(feature = "sqlx")]
+pub use sqlx_args::{SqlxQuery, SqlxSetArgs, SqlxValuesArgs, SqlxWhereArgs};
+pub use upsert::Upsert;
+
 /// `FieldsAttributeMacro` trait 定义了处理字段属性宏的功能。
 ///
 /// 这个 trait 主要用于解析和处理 `#[fields(...)]` 属性宏，该宏用于
@@ -11,7 +47,32 @@ pub trait FieldsAttributeMacro {
     /// 对于 `SELECT` 部分，更推荐使用 `SelectAttributeMacro`
     ///
     /// 返回值是一个包含字段名称的 `String` 向量。
-    fn generate_fields_clause() -> Vec<String>;
+    fn generate_fields_clause() -> Vec<String> {
+        Self::generate_fields_fragments().into_strings()
+    }
+    /// 与 `generate_fields_clause` 相同，但使用 `dialect` 的引号包裹字段名，
+    /// 只对设置了 `quote_identifiers` 或 `quote = true` 的字段生效
+    fn generate_fields_clause_for(dialect: Dialect) -> Vec<String> {
+        Self::generate_fields_fragments_for(dialect).into_strings()
+    }
+    /// 与 `generate_fields_clause` 相同，但返回带有来源字段等信息的 `Clause`
+    fn generate_fields_fragments() -> Clause;
+    /// 与 `generate_fields_clause_for` 相同，但返回带有来源字段等信息的 `Clause`
+    fn generate_fields_fragments_for(dialect: Dialect) -> Clause;
+    /// 生成 `kind` 语句中返回字段的子句，如 `RETURNING id, title`。
+    ///
+    /// MsSql 生成 `OUTPUT INSERTED.id, INSERTED.title`，`DELETE` 语句中为 `DELETED.id`，
+    /// 需要放在 `VALUES`、`WHERE` 之前，其余数据库放在语句的末尾。
+    ///
+    /// # Errors
+    /// 数据库不支持在 `kind` 语句中返回数据时返回 `SqlToolError::UnsupportedReturning`，
+    /// 如 MySql，以及 MariaDB 的 `UPDATE` 语句，见 `Dialect::supports_returning`
+    fn generate_returning_clause(
+        dialect: Dialect,
+        kind: StatementKind,
+    ) -> Result<String, SqlToolError> {
+        returning::returning_clause(Self::generate_fields_fragments_for(dialect), dialect, kind)
+    }
 }
 
 // 目前需要考虑到的内容有：
@@ -41,7 +102,39 @@ pub trait SelectAttributeMacro {
     /// 对于 `RETURNING` 或 `INSERT` 更推荐使用 `FieldsAttributeMacro`
     ///
     /// 返回值是一个包含字段名称的 `String` 向量。
-    fn generate_select_clause() -> Vec<String>;
+    fn generate_select_clause() -> Vec<String> {
+        Self::generate_select_fragments().into_strings()
+    }
+    /// 与 `generate_select_clause` 相同，但使用 `dialect` 的引号包裹字段名，
+    /// 只对设置了 `quote_identifiers` 或 `quote = true` 的字段生效
+    fn generate_select_clause_for(dialect: Dialect) -> Vec<String> {
+        Self::generate_select_fragments_for(dialect).into_strings()
+    }
+    /// 与 `generate_select_clause` 相同，但在每个字段名前加上表别名 `alias`，如 `u.id`。
+    ///
+    /// `rename` 为表达式或已经带有表名（如 `count(*)`、`f.id`）的字段不会加上表别名。
+    fn generate_select_clause_with_alias(alias: &str) -> Vec<String> {
+        Self::generate_select_fragments_with_alias(alias).into_strings()
+    }
+    /// 与 `generate_select_clause` 相同，但返回带有来源字段等信息的 `Clause`
+    fn generate_select_fragments() -> Clause;
+    /// 与 `generate_select_clause_for` 相同，但返回带有来源字段等信息的 `Clause`
+    fn generate_select_fragments_for(dialect: Dialect) -> Clause;
+    /// 与 `generate_select_clause_with_alias` 相同，但返回带有来源字段等信息的 `Clause`
+    fn generate_select_fragments_with_alias(alias: &str) -> Clause;
+    /// 与 `FieldsAttributeMacro::generate_returning_clause` 相同，但使用 `#[select(...)]` 生成的字段。
+    ///
+    /// 为了避免同时派生 `GenFields` 时名称冲突，这里使用 `select` 前缀
+    fn generate_select_returning_clause(
+        dialect: Dialect,
+        kind: StatementKind,
+    ) -> Result<String, SqlToolError> {
+        returning::returning_clause(Self::generate_select_fragments_for(dialect), dialect, kind)
+    }
+    /// 返回结构体上 `#[config(table = "...")]` 设置的表名，未设置时返回 `None`
+    fn table_name() -> Option<&'static str>;
+    /// 返回结构体上 `#[config(alias = "...")]` 设置的表别名，未设置时返回 `None`
+    fn table_alias() -> Option<&'static str>;
 }
 
 /// `ValuesAttributeMacro` trait 定义了处理字段属性宏的功能。
@@ -58,10 +151,60 @@ pub trait ValuesAttributeMacro {
     /// 返回值是一个包含字段名称的 `String` 向量。
     /// 数据库选择为 Postgres 数据应返回为 ["$1", "$2", "$3", ...]
     /// 数据库选择为 MySql 数据应返回为 ["?", "?", ...]
-    fn generate_values_clause() -> Vec<String>;
-    // /// 根据 `generate_values_clause` 最终生成的列表，返回最后的占位符索引号
-    // /// 例如 Postgres 中最末尾为 `$3`, 则此处应该返回 `3`
-    // fn last_param_index() -> usize;
+    fn generate_values_clause() -> Vec<String> {
+        Self::generate_values_fragments().into_strings()
+    }
+    /// 与 `generate_values_clause` 相同，但使用 `dialect` 渲染占位符，忽略 `#[config(database = "...")]`
+    fn generate_values_clause_for(dialect: Dialect) -> Vec<String>;
+    /// 返回下一个可用的占位符序号，用于在同一条语句中继续生成其他部分。
+    ///
+    /// 例如 Postgres 中最末尾为 `$3`，则此处返回 `4`；设置了 `index` 的字段按照其中最大的序号计算。
+    fn next_param_index() -> usize;
+    /// 从 `counter` 的下一个序号开始生成，并使用 `counter` 的数据库渲染占位符，生成之后更新 `counter`
+    fn generate_values_clause_with_counter(counter: &mut ParamCounter) -> Vec<String> {
+        Self::generate_values_fragments_with_counter(counter).into_strings()
+    }
+    /// 与 `generate_values_clause` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_values_fragments() -> Clause;
+    /// 与 `generate_values_clause_with_counter` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_values_fragments_with_counter(counter: &mut ParamCounter) -> Clause;
+    /// 返回在编译期计算的占位符序号与字段名的对应关系，按照序号排序。
+    ///
+    /// 通过 `index` 引用其他字段占位符的字段不会出现在其中，
+    /// 例如 `[(1, "title"), (2, "image_url")]` 表示 `$1` 绑定 `title`，`$2` 绑定 `image_url`。
+    fn placeholder_map() -> &'static [(usize, &'static str)];
+    /// 生成 `n` 行值，用于批量插入，每个元素为一条语句的 `VALUES` 部分。
+    ///
+    /// Postgres 输出为 `["($1, $2), ($3, $4), ..."]`，MySql 输出为 `["(?, ?), (?, ?), ..."]`。
+    /// 参数个数或行数超过数据库的限制（`Dialect::max_parameters`、`Dialect::max_rows`）时拆分为多条语句，
+    /// 每条语句的占位符都从 `#[config(index = ...)]` 重新开始；`n` 为 `0` 时返回空的 `Vec`。
+    fn generate_values_rows(n: usize) -> Vec<String>;
+    /// 与 `generate_values_rows` 相同，但使用 `dialect` 渲染占位符，忽略 `#[config(database = "...")]`
+    fn generate_values_rows_for(n: usize, dialect: Dialect) -> Vec<String> {
+        let rows_per_statement = Self::rows_per_statement(dialect);
+        let mut statements = Vec::new();
+        let mut start = 0;
+        while start < n {
+            let end = n.min(start.saturating_add(rows_per_statement));
+            let rows: Vec<String> = (0..end - start)
+                .map(|row| {
+                    format!(
+                        "({})",
+                        Self::generate_values_row_fragments(row, dialect).join(", ")
+                    )
+                })
+                .collect();
+            statements.push(rows.join(", "));
+            start = end;
+        }
+        statements
+    }
+    /// 生成一条语句中的第 `row` 行（从 `0` 开始），占位符序号在第一行的基础上依次后移。
+    ///
+    /// 设置了 `index` 的字段同样后移，小于 `#[config(index = ...)]` 的序号引用的是语句中其他部分的参数，不会后移。
+    fn generate_values_row_fragments(row: usize, dialect: Dialect) -> Clause;
+    /// 使用 `dialect` 时单条语句最多可以插入的行数，至少为 `1`
+    fn rows_per_statement(dialect: Dialect) -> usize;
 }
 
 /// `WhereAttributeMacro` trait 定义了处理字段属性宏的功能。
@@ -78,10 +221,59 @@ pub trait WhereAttributeMacro {
     /// 返回值是一个包含字段名称的 `String` 向量。
     /// 数据库选择为 Postgres 数据应返回为 ["field1 [condition] $1", "[condition_all]", "[rename] [condition] $3", "field4 [condtion] $[index]"]
     /// 数据库选择为 MySql 数据应返回为 ["field1 [condition] ?", "[condition_all]", "[rename] [condition] ?", "field4 [condtion] ?"]
-    fn generate_where_clause(&self) -> Vec<String>;
-    // /// 返回最终的索引号
-    // fn last_param_index(&self) -> usize;
+    fn generate_where_clause(&self) -> Vec<String> {
+        self.generate_where_fragments().into_strings()
+    }
+    /// 与 `generate_where_clause` 相同，但使用 `dialect` 渲染占位符，忽略 `#[config(database = "...")]`
+    fn generate_where_clause_for(&self, dialect: Dialect) -> Vec<String>;
+    /// 返回 `generate_where_clause` 之后下一个可用的占位符序号。
+    ///
+    /// 结果取决于字段的值，被 `ignore_none` 过滤掉的字段不会占用序号，
+    /// 设置了 `index` 的字段按照其中最大的序号计算。
+    fn next_param_index(&self) -> usize;
+    /// 返回 `generate_where_clause_with_index(index)` 之后下一个可用的占位符序号
+    fn next_param_index_with_index(&self, index: usize) -> usize;
+    /// 从 `counter` 的下一个序号开始生成，并使用 `counter` 的数据库渲染占位符，生成之后更新 `counter`。
+    ///
+    /// 多个结构体依次使用同一个 `counter` 时，生成的占位符是连续的。
+    fn generate_where_clause_with_counter(&self, counter: &mut ParamCounter) -> Vec<String> {
+        self.generate_where_fragments_with_counter(counter)
+            .into_strings()
+    }
     fn generate_where_clause_with_index(&self, index: usize) -> Vec<String>;
+    /// 与 `generate_where_clause_with_index` 相同，但使用 `dialect` 渲染占位符
+    fn generate_where_clause_with_index_for(&self, index: usize, dialect: Dialect) -> Vec<String>;
+    /// 与 `generate_where_clause` 相同，但在每个字段名前加上表别名 `alias`，如 `u.id = $1`。
+    ///
+    /// 只替换 `{name}`，`rename` 为表达式或已经带有表名的字段不会加上表别名。
+    fn generate_where_clause_with_alias(&self, alias: &str) -> Vec<String> {
+        self.generate_where_fragments_with_alias(alias)
+            .into_strings()
+    }
+    /// 与 `generate_where_clause` 相同，同时返回实际参与生成的字段值。
+    ///
+    /// 返回的参数列表与占位符的顺序一致，被 `ignore` 或 `ignore_none` 过滤掉的字段、
+    /// 以及通过 `value` 直接替换占位符的字段都不会出现在参数列表中。
+    /// 对于 MySql 等使用 `?` 的数据库，`condition_all` 中每出现一次 `{index}` 都会对应一个参数。
+    fn generate_where_clause_with_args(&self) -> (Vec<String>, Vec<SqlArg<'_>>);
+    /// 与 `generate_where_clause_with_args` 相同，但使用 `dialect` 渲染占位符。
+    ///
+    /// 参数的数量同样取决于 `dialect`，使用 `?` 的数据库每个占位符都会对应一个参数。
+    fn generate_where_clause_with_args_for(
+        &self,
+        dialect: Dialect,
+    ) -> (Vec<String>, Vec<SqlArg<'_>>);
+    /// 与 `generate_where_clause` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_where_fragments(&self) -> Clause;
+    /// 与 `generate_where_clause_with_counter` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_where_fragments_with_counter(&self, counter: &mut ParamCounter) -> Clause;
+    /// 与 `generate_where_clause_with_alias` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_where_fragments_with_alias(&self, alias: &str) -> Clause;
+    /// 返回在编译期计算的占位符序号与字段名的对应关系，按照序号排序。
+    ///
+    /// 计算时假设所有字段都会生成占位符，被 `ignore_none` 过滤掉的字段在运行时会使之后的序号前移。
+    /// `#[r#where(in)]` 的列表字段和 `#[r#where(range)]` 的范围字段按照一个占位符计算，运行时每个元素或边界占用一个序号。
+    fn placeholder_map(&self) -> &'static [(usize, &'static str)];
 }
 
 /// `SetAttributeMacro` trait 定义了处理字段属性宏的功能。
@@ -97,7 +289,16 @@ pub trait SetAttributeMacro {
     ///
     /// 返回值是一个包含字段名称的 `String` 向量。
     /// 数据应返回为 ["field1 = $1", "field1 = $[2]", "[rename] = $3", ...]
-    fn generate_set_clause(&self) -> Vec<String>;
+    fn generate_set_clause(&self) -> Vec<String> {
+        self.generate_set_fragments().into_strings()
+    }
+    /// 与 `generate_set_clause` 相同，但使用 `dialect` 渲染占位符，忽略 `#[config(database = "...")]`
+    fn generate_set_clause_for(&self, dialect: Dialect) -> Vec<String>;
+    /// 从 `counter` 的下一个序号开始生成，并使用 `counter` 的数据库渲染占位符，生成之后更新 `counter`
+    fn generate_set_clause_with_counter(&self, counter: &mut ParamCounter) -> Vec<String> {
+        self.generate_set_fragments_with_counter(counter)
+            .into_strings()
+    }
     // /// 解析 `#[set(where = "{name} > {index}")]`, 并生成相应的条件列表。
     // /// 对应的使用方法有：
     // /// `where = "{name} > $1"`, 不存在index 则不进行替换
@@ -110,10 +311,101 @@ pub trait SetAttributeMacro {
     // /// 数据应返回为 ["field4 [where] $4", "rename [where] ${index}"]
     // /// 如果想要使用好的自定义方法，应该使用 `GenWhere` 宏， 并使用 `ignore` 忽略指定值
     // fn generate_where_clause(&self) -> Vec<String>;
-    // /// 返回最终的索引号
-    // fn last_param_index() -> usize;
 
     /// 当存在 `#[set(where, ...)]` 或 `#[set(where = "..."), ...]`
     /// 可以使用该方法返回包含 set 和 where 语句需要的数组的元组
-    fn generate_set_and_where_clause(&self) -> (Vec<String>, Vec<String>);
+    fn generate_set_and_where_clause(&self) -> (Vec<String>, Vec<String>) {
+        let (set_data, where_data) = self.generate_set_and_where_fragments();
+        (set_data.into_strings(), where_data.into_strings())
+    }
+    /// 与 `generate_set_and_where_clause` 相同，但使用 `dialect` 渲染占位符
+    fn generate_set_and_where_clause_for(&self, dialect: Dialect) -> (Vec<String>, Vec<String>);
+    /// 与 `generate_set_and_where_clause` 相同，但从 `counter` 的下一个序号开始生成，生成之后更新 `counter`
+    fn generate_set_and_where_clause_with_counter(
+        &self,
+        counter: &mut ParamCounter,
+    ) -> (Vec<String>, Vec<String>) {
+        let (set_data, where_data) = self.generate_set_and_where_fragments_with_counter(counter);
+        (set_data.into_strings(), where_data.into_strings())
+    }
+    /// 返回 `generate_set_and_where_clause` 之后下一个可用的占位符序号。
+    ///
+    /// 可以将返回值传给另一个结构体的 `generate_where_clause_with_index`，使整条语句的占位符连续。
+    /// 结果取决于字段的值，被 `ignore_none` 过滤掉的字段不会占用序号。
+    fn next_param_index(&self) -> usize;
+
+    /// 生成完整的 `UPDATE` 语句，表名来自结构体上的 `#[config(table = "...")]`。
+    ///
+    /// `WHERE` 部分来自 `#[set(r#where)]` 或 `#[set(r#where = "...")]` 标记的字段。
+    /// 返回值为 `UPDATE table SET field1 = $1, field2 = $2 WHERE field3 = $3 AND ...`
+    ///
+    /// # Errors
+    /// - 没有设置表名时返回 `SqlToolError::MissingTable`
+    /// - 没有需要更新的字段时返回 `SqlToolError::EmptySet`
+    /// - 没有任何 `WHERE` 条件且没有设置 `#[config(allow_full_update = true)]` 时返回 `SqlToolError::MissingWhere`
+    fn generate_update_sql(&self) -> Result<String, SqlToolError>;
+    /// 与 `generate_update_sql` 相同，但使用 `dialect` 渲染占位符
+    fn generate_update_sql_for(&self, dialect: Dialect) -> Result<String, SqlToolError>;
+    /// 与 `generate_set_clause` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_set_fragments(&self) -> Clause;
+    /// 与 `generate_set_clause_with_counter` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_set_fragments_with_counter(&self, counter: &mut ParamCounter) -> Clause;
+    /// 与 `generate_set_and_where_clause` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_set_and_where_fragments(&self) -> (Clause, Clause);
+    /// 与 `generate_set_and_where_clause_with_counter` 相同，但返回带有来源字段、占位符序号等信息的 `Clause`
+    fn generate_set_and_where_fragments_with_counter(
+        &self,
+        counter: &mut ParamCounter,
+    ) -> (Clause, Clause);
+    /// 返回在编译期计算的占位符序号与字段名的对应关系，包含 `SET` 和 `WHERE` 两部分，按照序号排序。
+    ///
+    /// 计算时假设所有字段都会生成占位符，被 `ignore_none` 过滤掉的字段在运行时会使之后的序号前移。
+    fn placeholder_map(&self) -> &'static [(usize, &'static str)];
+}
+
+/// `InsertAttributeMacro` trait 定义了生成完整 `INSERT` 语句的功能。
+///
+/// 这个 trait 由 `GenInsert` 派生宏实现，字段部分复用 `#[field(...)]` 属性宏，
+/// 值部分复用 `#[value(...)]` 属性宏，表名来自结构体上的 `#[config(table = "...")]`。
+pub trait InsertAttributeMacro {
+    /// 生成完整的 `INSERT` 语句。
+    ///
+    /// 返回值为 `INSERT INTO table (field1, field2, ...) VALUES ($1, $2, ...)`
+    fn generate_insert_sql() -> String;
+    /// 与 `generate_insert_sql` 相同，但使用 `dialect` 渲染占位符，忽略 `#[config(database = "...")]`
+    fn generate_insert_sql_for(dialect: Dialect) -> String;
+    /// 生成 upsert 语句，冲突字段来自 `#[config(conflict = [...])]`，
+    /// 发生冲突时更新 `#[value(conflict_update)]` 标记的字段，没有标记任何字段时忽略冲突的行。
+    ///
+    /// Postgres、SQLite 使用 `ON CONFLICT`，MySql、MariaDB 使用 `ON DUPLICATE KEY UPDATE`，MsSql 使用 `MERGE`，
+    /// 具体格式见 `Upsert`。
+    ///
+    /// # Errors
+    /// 没有设置 `#[config(conflict = [...])]` 时返回 `SqlToolError::MissingConflict`
+    fn generate_upsert_sql() -> Result<String, SqlToolError>;
+    /// 与 `generate_upsert_sql` 相同，但使用 `dialect` 渲染占位符，忽略 `#[config(database = "...")]`
+    fn generate_upsert_sql_for(dialect: Dialect) -> Result<String, SqlToolError>;
+}
+
+/// `DeleteAttributeMacro` trait 定义了生成完整 `DELETE` 语句的功能。
+///
+/// 这个 trait 由 `GenDelete` 派生宏实现，`WHERE` 部分复用 `#[r#where(...)]` 属性宏，
+/// 表名来自结构体上的 `#[config(table = "...")]`。
+pub trait DeleteAttributeMacro {
+    /// 生成完整的 `DELETE` 语句。
+    ///
+    /// 返回值为 `DELETE FROM table WHERE field1 = $1 AND ...`
+    ///
+    /// # Errors
+    /// 所有条件都被过滤掉且没有设置 `#[config(allow_full_delete = true)]` 时返回 `SqlToolError::MissingWhere`
+    fn generate_delete_sql(&self) -> Result<String, SqlToolError>;
+    /// 与 `generate_delete_sql` 相同，但使用 `dialect` 渲染占位符
+    fn generate_delete_sql_for(&self, dialect: Dialect) -> Result<String, SqlToolError>;
+    /// 与 `generate_delete_sql` 相同，同时返回按占位符顺序排列的参数列表。
+    fn generate_delete_sql_with_args(&self) -> Result<(String, Vec<SqlArg<'_>>), SqlToolError>;
+    /// 与 `generate_delete_sql_with_args` 相同，但使用 `dialect` 渲染占位符
+    fn generate_delete_sql_with_args_for(
+        &self,
+        dialect: Dialect,
+    ) -> Result<(String, Vec<SqlArg<'_>>), SqlToolError>;
 }
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::Dialect;
+
+/// `LikeMode` 表示 `#[r#where(like = "...")]` 的匹配方式。
+///
+/// 生成的条件使用 `ESCAPE '!'`，绑定的值会先转义其中的通配符，再按照匹配方式加上 `%`，
+/// 因此字段中的 `%`、`_` 只会按照字面值匹配。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LikeMode {
+    /// `like = "contains"`，匹配包含该值的数据，生成 `%value%`
+    Contains,
+    /// `like = "prefix"`，匹配以该值开头的数据，生成 `value%`
+    Prefix,
+    /// `like = "suffix"`，匹配以该值结尾的数据，生成 `%value`
+    Suffix,
+}
+
+impl LikeMode {
+    /// 生成的 `ESCAPE` 子句使用的转义字符。
+    ///
+    /// 没有使用 `\`，因为 MySql 默认会将字符串字面量中的 `\` 视为转义字符。
+    pub const ESCAPE: char = '!';
+
+    /// 转义 `value` 中的通配符，并按照匹配方式加上 `%`。
+    ///
+    /// 转义 `%`、`_` 和转义字符本身，MsSql 中 `[` 也是通配符，同样会被转义。
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::{Dialect, LikeMode};
+    ///
+    /// assert_eq!(LikeMode::Contains.wrap("标题", Dialect::Postgres), "%标题%");
+    /// assert_eq!(LikeMode::Prefix.wrap("100%_!", Dialect::MySql), "100!%!_!!%");
+    /// assert_eq!(LikeMode::Suffix.wrap("[a]", Dialect::MsSql), "%![a]");
+    /// ```
+    pub fn wrap(&self, value: &str, dialect: Dialect) -> String {
+        let mut pattern = String::with_capacity(value.len() + 2);
+        if matches!(self, LikeMode::Contains | LikeMode::Suffix) {
+            pattern.push('%');
+        }
+        for char in value.chars() {
+            let wildcard = match char {
+                '%' | '_' | Self::ESCAPE => true,
+                '[' => dialect == Dialect::MsSql,
+                _ => false,
+            };
+            if wildcard {
+                pattern.push(Self::ESCAPE);
+            }
+            pattern.push(char);
+        }
+        if matches!(self, LikeMode::Contains | LikeMode::Prefix) {
+            pattern.push('%');
+        }
+        pattern
+    }
+
+    /// 由 `LikeMode as u8` 还原，供 `LikeParam` 使用
+    #[cfg(any(feature = "rusqlite", feature = "postgres"))]
+    pub(crate) fn from_u8(mode: u8) -> Self {
+        match mode {
+            mode if mode == LikeMode::Prefix as u8 => LikeMode::Prefix,
+            mode if mode == LikeMode::Suffix as u8 => LikeMode::Suffix,
+            _ => LikeMode::Contains,
+        }
+    }
+}
+
+/// `LikeValue` 表示可以使用 `#[r#where(like = "...")]` 的字段类型，
+/// 如 `String`、`&str` 以及它们的 `Option`。
+pub trait LikeValue {
+    /// 实际绑定的值的类型，字符串为 `String`，`Option` 为 `Option<String>`
+    type Pattern;
+
+    /// 返回按照 `mode` 转义并加上 `%` 之后的值，见 `LikeMode::wrap`
+    fn to_pattern(&self, mode: LikeMode, dialect: Dialect) -> Self::Pattern;
+}
+
+impl LikeValue for str {
+    type Pattern = String;
+
+    fn to_pattern(&self, mode: LikeMode, dialect: Dialect) -> Self::Pattern {
+        mode.wrap(self, dialect)
+    }
+}
+
+impl LikeValue for String {
+    type Pattern = String;
+
+    fn to_pattern(&self, mode: LikeMode, dialect: Dialect) -> Self::Pattern {
+        mode.wrap(self, dialect)
+    }
+}
+
+impl<T: LikeValue + ?Sized> LikeValue for &T {
+    type Pattern = T::Pattern;
+
+    fn to_pattern(&self, mode: LikeMode, dialect: Dialect) -> Self::Pattern {
+        (**self).to_pattern(mode, dialect)
+    }
+}
+
+impl<T: LikeValue> LikeValue for Option<T> {
+    type Pattern = Option<T::Pattern>;
+
+    fn to_pattern(&self, mode: LikeMode, dialect: Dialect) -> Self::Pattern {
+        self.as_ref().map(|value| value.to_pattern(mode, dialect))
+    }
+}
+
+/// 派生宏生成的 `rusqlite`、`postgres` 参数方法中 `like` 字段使用的参数类型。
+///
+/// 这两个驱动的参数列表只保存引用，返回的参数不能引用方法中生成的值，
+/// 因此 `LikeParam` 通过 `RefCast` 由字段的引用直接转换得到，在转换为数据库参数时才生成转义后的值。
+/// `MODE` 为 `LikeMode as u8`。
+#[doc(hidden)]
+#[cfg_attr(
+    any(feature = "rusqlite", feature = "postgres"),
+    derive(ref_cast::RefCast)
+)]
+#[repr(transparent)]
+pub struct LikeParam<T: ?Sized, const MODE: u8>(T);
+
+impl<T: LikeValue + ?Sized, const MODE: u8> LikeParam<T, MODE> {
+    /// 将字段的引用转换为 `LikeParam` 的引用
+    #[cfg(any(feature = "rusqlite", feature = "postgres"))]
+    pub fn from_ref(value: &T) -> &Self {
+        ref_cast::RefCast::ref_cast(value)
+    }
+
+    /// 返回转义并加上 `%` 之后的值
+    #[cfg(any(feature = "rusqlite", feature = "postgres"))]
+    pub(crate) fn pattern(&self, dialect: Dialect) -> T::Pattern {
+        self.0.to_pattern(LikeMode::from_u8(MODE), dialect)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized, const MODE: u8> fmt::Debug for LikeParam<T, MODE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LikeParam").field(&&self.0).finish()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::Dialect;
+
+/// `BindPlan` 记录生成的占位符分别对应哪个结构体字段，用于按照占位符的顺序绑定参数。
+///
+/// 派生宏生成的绑定方法会在生成 SQL 的同时记录每个占位符的序号和字段，
+/// 再通过 `fields` 得到需要依次绑定的字段：
+/// - 带序号的占位符（如 `$1`）按照序号排序，重复引用的序号只绑定一次，优先绑定占位符所属的字段
+/// - 使用 `?` 的数据库按照占位符出现的顺序绑定，出现几次就绑定几次
+///
+/// # 示例
+/// ```
+/// use sql_tool_core::{BindPlan, Dialect};
+///
+/// // 字段 0 通过 `index` 引用 `$2`，字段 1 使用 `$1`，字段 2 使用 `$2`
+/// let mut plan = BindPlan::new(Dialect::Postgres);
+/// plan.push_reused(2, 0, 1);
+/// plan.push(1, 1, 1);
+/// plan.push(2, 2, 1);
+/// assert_eq!(plan.fields(), vec![1, 2]);
+///
+/// let mut plan = BindPlan::new(Dialect::MySql);
+/// plan.push(1, 0, 2);
+/// plan.push(2, 1, 1);
+/// assert_eq!(plan.fields(), vec![0, 0, 1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindPlan {
+    dialect: Dialect,
+    slots: Vec<(usize, usize, usize, bool)>,
+}
+
+impl BindPlan {
+    /// 创建一个空的 `BindPlan`
+    pub fn new(dialect: Dialect) -> Self {
+        BindPlan {
+            dialect,
+            slots: Vec::new(),
+        }
+    }
+
+    /// 记录字段 `field` 在序号为 `index` 的位置生成了 `count` 个占位符，由派生宏生成的代码调用。
+    ///
+    /// `field` 为字段在结构体中参与绑定的顺序，由派生宏决定。
+    pub fn push(&mut self, index: usize, field: usize, count: usize) {
+        if count > 0 {
+            self.slots.push((index, field, count, false));
+        }
+    }
+
+    /// 与 `push` 相同，但表示字段通过 `index` 引用了其他字段的占位符。
+    ///
+    /// 带序号的数据库只有在占位符所属的字段没有生成时才会绑定该字段，使用 `?` 的数据库与 `push` 相同。
+    pub fn push_reused(&mut self, index: usize, field: usize, count: usize) {
+        if count > 0 {
+            self.slots.push((index, field, count, true));
+        }
+    }
+
+    /// 按照占位符的顺序返回需要依次绑定的字段
+    pub fn fields(&self) -> Vec<usize> {
+        if self.dialect.numbered_placeholders() {
+            let mut slots = self.slots.clone();
+            slots.sort_by_key(|(index, _, _, reused)| (*index, *reused));
+            slots.dedup_by_key(|(index, _, _, _)| *index);
+            slots.into_iter().map(|(_, field, _, _)| field).collect()
+        } else {
+            self.slots
+                .iter()
+                .flat_map(|(_, field, count, _)| std::iter::repeat_n(*field, *count))
+                .collect()
+        }
+    }
+}
+
+/// `SlotMap` 将编译期计算的占位符序号重新编号为运行时连续的序号。
+///
+/// 设置了 `index` 的结构体中，`ignore_none` 的 `Option` 等字段在运行时不一定生成占位符，
+/// 派生宏生成的代码会先记录实际生成的占位符在编译期的序号，
+/// 再通过 `get` 将每个序号替换为从 `start` 开始、按照编译期序号排序的连续序号，
+/// 这样跳过的字段不会在之后的占位符中留下空缺，也不会让 `index` 引用到其他字段的占位符。
+///
+/// 小于起始索引 `base` 的序号指向结构体之外的占位符，不会重新编号。
+///
+/// # 示例
+/// ```
+/// use sql_tool_core::SlotMap;
+///
+/// // 编译期 `$1` 属于跳过的字段，`$2` 和 `index = 3` 的字段实际生成
+/// let mut slots = SlotMap::new(1, 1);
+/// slots.push(3);
+/// slots.push(2);
+/// assert_eq!(slots.get(2), 1);
+/// assert_eq!(slots.get(3), 2);
+/// assert_eq!(slots.next_index(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotMap {
+    base: usize,
+    start: usize,
+    slots: Vec<usize>,
+}
+
+impl SlotMap {
+    /// 创建一个空的 `SlotMap`，`base` 为编译期的起始索引，`start` 为运行时的起始索引
+    pub fn new(base: usize, start: usize) -> Self {
+        SlotMap {
+            base,
+            start,
+            slots: Vec::new(),
+        }
+    }
+
+    /// 记录编译期序号为 `slot` 的占位符在运行时生成，由派生宏生成的代码调用
+    pub fn push(&mut self, slot: usize) {
+        if slot < self.base {
+            return;
+        }
+        if let Err(position) = self.slots.binary_search(&slot) {
+            self.slots.insert(position, slot);
+        }
+    }
+
+    /// 编译期序号为 `slot` 的占位符在运行时的序号
+    pub fn get(&self, slot: usize) -> usize {
+        if slot < self.base {
+            return slot;
+        }
+        self.start + self.slots.partition_point(|used| *used < slot)
+    }
+
+    /// 所有记录的占位符之后下一个可用的序号
+    pub fn next_index(&self) -> usize {
+        self.start + self.slots.len()
+    }
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+
+use bytes::BytesMut;
+use postgres_types::{to_sql_checked, IsNull, ToSql, Type};
+
+use crate::{Dialect, LikeParam, LikeValue};
+
+/// `tokio-postgres` 和 `postgres` 查询时使用的参数类型
+pub type PgParam<'a> = &'a (dyn ToSql + Sync);
+
+/// `PgWhereParams` 由 `GenWhere` 在启用 `postgres` feature 时实现。
+///
+/// 只有 `#[config(database = "postgres")]` 的结构体会实现该 trait，
+/// 返回的参数列表与 `$1..$n` 一一对应，可以直接传给 `client.query(&sql, &params)`。
+pub trait PgWhereParams {
+    /// 按照 `generate_where_clause` 生成的占位符序号返回参数。
+    ///
+    /// 被 `ignore`、`ignore_none` 过滤掉的字段，以及通过 `value` 直接替换占位符的字段都不会出现在参数列表中；
+    /// 同一个序号出现多次时只返回一次。
+    fn to_pg_params(&self) -> Vec<PgParam<'_>>;
+}
+
+/// `PgSetParams` 由 `GenSet` 在启用 `postgres` feature 时实现。
+///
+/// 只有 `#[config(database = "postgres")]` 的结构体会实现该 trait。
+pub trait PgSetParams {
+    /// 按照 `generate_set_and_where_clause`（即 `generate_update_sql`）生成的占位符序号返回参数
+    fn to_pg_params(&self) -> Vec<PgParam<'_>>;
+}
+
+/// `PgValuesParams` 由 `GenValues` 在启用 `postgres` feature 时实现。
+///
+/// 只有 `#[config(database = "postgres")]` 的结构体会实现该 trait。
+pub trait PgValuesParams {
+    /// 按照 `generate_values_clause` 生成的占位符序号返回参数，
+    /// 通过 `#[value(index = ...)]` 重复引用的序号只返回一次。
+    fn to_pg_params(&self) -> Vec<PgParam<'_>>;
+}
+
+impl<T, const MODE: u8> ToSql for LikeParam<T, MODE>
+where
+    T: LikeValue + std::fmt::Debug,
+    T::Pattern: ToSql,
+{
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.pattern(Dialect::Postgres).to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <T::Pattern as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
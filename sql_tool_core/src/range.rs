@@ -0,0 +1,115 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::Dialect;
+
+/// `RangeValue` 表示可以使用 `#[r#where(range)]` 的字段类型。
+///
+/// 支持 `Range<T>`、`RangeInclusive<T>`、`(Option<T>, Option<T>)` 以及它们的 `Option`，
+/// 每个存在的边界占用一个占位符，按照起始、结束的顺序绑定。
+pub trait RangeValue {
+    /// 边界的类型
+    type Bound;
+
+    /// 返回起始和结束边界，不存在的边界为 `None`
+    fn bounds(&self) -> (Option<&Self::Bound>, Option<&Self::Bound>);
+
+    /// 结束边界是否包含在范围内，只有 `Range<T>` 为 `false`
+    fn end_inclusive(&self) -> bool {
+        true
+    }
+
+    /// 生成 `column` 的范围条件，`index` 为第一个边界的占位符序号。
+    ///
+    /// - 两侧边界都存在时生成 `column BETWEEN $1 AND $2`，`Range<T>` 生成 `column >= $1 AND column < $2`
+    /// - 只有一侧边界时生成 `column >= $1` 或 `column <= $1`（`Range<T>` 为 `<`）
+    /// - 两侧边界都不存在时返回 `None`
+    ///
+    /// # 示例
+    /// ```
+    /// use sql_tool_core::{Dialect, RangeValue};
+    ///
+    /// let condition = (1..=10).range_condition("id", Dialect::Postgres, 2);
+    /// assert_eq!(condition.as_deref(), Some("id BETWEEN $2 AND $3"));
+    /// let condition = (1..10).range_condition("id", Dialect::MySql, 1);
+    /// assert_eq!(condition.as_deref(), Some("id >= ? AND id < ?"));
+    /// let condition = (None, Some(10)).range_condition("id", Dialect::Postgres, 1);
+    /// assert_eq!(condition.as_deref(), Some("id <= $1"));
+    /// ```
+    fn range_condition(&self, column: &str, dialect: Dialect, index: usize) -> Option<String> {
+        let end = if self.end_inclusive() { "<=" } else { "<" };
+        match self.bounds() {
+            (Some(_), Some(_)) if self.end_inclusive() => Some(format!(
+                "{} BETWEEN {} AND {}",
+                column,
+                dialect.placeholder(index),
+                dialect.placeholder(index + 1)
+            )),
+            (Some(_), Some(_)) => Some(format!(
+                "{} >= {} AND {} {} {}",
+                column,
+                dialect.placeholder(index),
+                column,
+                end,
+                dialect.placeholder(index + 1)
+            )),
+            (Some(_), None) => Some(format!("{} >= {}", column, dialect.placeholder(index))),
+            (None, Some(_)) => Some(format!("{} {} {}", column, end, dialect.placeholder(index))),
+            (None, None) => None,
+        }
+    }
+}
+
+impl<T> RangeValue for Range<T> {
+    type Bound = T;
+
+    fn bounds(&self) -> (Option<&T>, Option<&T>) {
+        (Some(&self.start), Some(&self.end))
+    }
+
+    fn end_inclusive(&self) -> bool {
+        false
+    }
+}
+
+impl<T> RangeValue for RangeInclusive<T> {
+    type Bound = T;
+
+    fn bounds(&self) -> (Option<&T>, Option<&T>) {
+        (Some(self.start()), Some(self.end()))
+    }
+}
+
+impl<T> RangeValue for (Option<T>, Option<T>) {
+    type Bound = T;
+
+    fn bounds(&self) -> (Option<&T>, Option<&T>) {
+        (self.0.as_ref(), self.1.as_ref())
+    }
+}
+
+impl<R: RangeValue> RangeValue for Option<R> {
+    type Bound = R::Bound;
+
+    fn bounds(&self) -> (Option<&Self::Bound>, Option<&Self::Bound>) {
+        match self {
+            Some(range) => range.bounds(),
+            None => (None, None),
+        }
+    }
+
+    fn end_inclusive(&self) -> bool {
+        self.as_ref().is_none_or(RangeValue::end_inclusive)
+    }
+}
+
+impl<R: RangeValue + ?Sized> RangeValue for &R {
+    type Bound = R::Bound;
+
+    fn bounds(&self) -> (Option<&Self::Bound>, Option<&Self::Bound>) {
+        (**self).bounds()
+    }
+
+    fn end_inclusive(&self) -> bool {
+        (**self).end_inclusive()
+    }
+}
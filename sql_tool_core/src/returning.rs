@@ -0,0 +1,59 @@
+use std::fmt;
+
+use crate::{Clause, Dialect, SqlToolError};
+
+/// `StatementKind` 表示需要返回数据的语句类型。
+///
+/// 不同的数据库对 `RETURNING` 的支持不同，例如 MariaDB 只支持 `INSERT` 和 `DELETE`，
+/// MsSql 的 `OUTPUT` 在 `DELETE` 中需要使用 `DELETED.col`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    /// `INSERT` 语句
+    Insert,
+    /// `UPDATE` 语句
+    Update,
+    /// `DELETE` 语句
+    Delete,
+}
+
+impl fmt::Display for StatementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StatementKind::Insert => "INSERT",
+            StatementKind::Update => "UPDATE",
+            StatementKind::Delete => "DELETE",
+        })
+    }
+}
+
+/// 使用 `columns` 生成 `RETURNING` 或 `OUTPUT` 子句，供 `generate_returning_clause` 使用。
+///
+/// MsSql 只在普通的字段名前加上 `INSERTED.` 或 `DELETED.`，`rename` 为表达式的字段保持不变。
+pub(crate) fn returning_clause(
+    columns: Clause,
+    dialect: Dialect,
+    kind: StatementKind,
+) -> Result<String, SqlToolError> {
+    if !dialect.supports_returning(kind) {
+        return Err(SqlToolError::UnsupportedReturning(dialect, kind));
+    }
+    if dialect != Dialect::MsSql {
+        return Ok(format!("RETURNING {}", columns.join(", ")));
+    }
+    let prefix = match kind {
+        StatementKind::Delete => "DELETED.",
+        StatementKind::Insert | StatementKind::Update => "INSERTED.",
+    };
+    let columns = columns
+        .iter()
+        .map(|column| {
+            if column.is_expression() {
+                column.to_string()
+            } else {
+                format!("{}{}", prefix, column)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(format!("OUTPUT {}", columns))
+}
@@ -0,0 +1,43 @@
+use rusqlite::types::{ToSqlOutput, Value};
+use rusqlite::ToSql;
+
+use crate::{Dialect, LikeParam, LikeValue};
+
+/// `RusqliteWhereParams` 由 `GenWhere` 在启用 `rusqlite` feature 时实现。
+///
+/// 只有 `#[config(database = "sqlite")]` 的结构体会实现该 trait，
+/// 返回的参数列表可以直接作为 `rusqlite` 的参数使用，如 `conn.query_row(&sql, params.as_slice(), ...)`。
+pub trait RusqliteWhereParams {
+    /// 按照 `generate_where_clause` 生成的 `?` 的顺序返回参数。
+    ///
+    /// 被 `ignore`、`ignore_none` 过滤掉的字段，以及通过 `value` 直接替换占位符的字段都不会出现在参数列表中；
+    /// `condition_all` 中每出现一次 `{index}` 都会对应一个参数。
+    fn to_rusqlite_params(&self) -> Vec<&dyn ToSql>;
+}
+
+/// `RusqliteSetParams` 由 `GenSet` 在启用 `rusqlite` feature 时实现。
+///
+/// 只有 `#[config(database = "sqlite")]` 的结构体会实现该 trait。
+pub trait RusqliteSetParams {
+    /// 按照 `generate_set_and_where_clause`（即 `generate_update_sql`）生成的 `?` 的顺序返回参数，
+    /// 先返回 `SET` 部分，再返回 `WHERE` 部分。
+    fn to_rusqlite_params(&self) -> Vec<&dyn ToSql>;
+}
+
+/// `RusqliteValuesParams` 由 `GenValues` 在启用 `rusqlite` feature 时实现。
+///
+/// 只有 `#[config(database = "sqlite")]` 的结构体会实现该 trait。
+pub trait RusqliteValuesParams {
+    /// 按照 `generate_values_clause` 生成的 `?` 的顺序返回参数
+    fn to_rusqlite_params(&self) -> Vec<&dyn ToSql>;
+}
+
+impl<T, const MODE: u8> ToSql for LikeParam<T, MODE>
+where
+    T: LikeValue + ?Sized,
+    T::Pattern: Into<Value>,
+{
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Owned(self.pattern(Dialect::Sqlite).into()))
+    }
+}
@@ -0,0 +1,55 @@
+use sqlx::query::Query;
+use sqlx::Database;
+
+/// `sqlx::query` 返回的查询类型
+pub type SqlxQuery<'q, DB> = Query<'q, DB, <DB as Database>::Arguments<'q>>;
+
+/// `SqlxWhereArgs` 由 `GenWhere` 在启用 `sqlx` feature 时实现，用于将字段绑定到 `sqlx` 的查询上。
+///
+/// `Database` 由结构体上的 `#[config(database = "...")]` 决定：
+/// `postgres` 对应 `sqlx::Postgres`，`mysql` 和 `mariadb` 对应 `sqlx::MySql`，`sqlite` 对应 `sqlx::Sqlite`。
+/// `sqlx` 不支持 `mssql`，因此 `database = "mssql"` 的结构体不会实现该 trait。
+pub trait SqlxWhereArgs {
+    /// 绑定参数使用的数据库
+    type Database: Database;
+
+    /// 按照 `generate_where_clause` 生成的占位符顺序绑定字段。
+    ///
+    /// 只绑定实际生成了占位符的字段，被 `ignore`、`ignore_none` 过滤掉的字段，
+    /// 以及通过 `value` 直接替换占位符的字段都不会被绑定。
+    fn bind_where_args<'q>(
+        &'q self,
+        query: SqlxQuery<'q, Self::Database>,
+    ) -> SqlxQuery<'q, Self::Database>;
+}
+
+/// `SqlxSetArgs` 由 `GenSet` 在启用 `sqlx` feature 时实现，用于将字段绑定到 `sqlx` 的查询上。
+///
+/// `Database` 的对应关系与 `SqlxWhereArgs` 相同。
+pub trait SqlxSetArgs {
+    /// 绑定参数使用的数据库
+    type Database: Database;
+
+    /// 按照 `generate_set_and_where_clause`（即 `generate_update_sql`）生成的占位符顺序绑定字段，
+    /// 先绑定 `SET` 部分，再绑定 `WHERE` 部分。
+    fn bind_set_args<'q>(
+        &'q self,
+        query: SqlxQuery<'q, Self::Database>,
+    ) -> SqlxQuery<'q, Self::Database>;
+}
+
+/// `SqlxValuesArgs` 由 `GenValues` 在启用 `sqlx` feature 时实现，用于将字段绑定到 `sqlx` 的查询上。
+///
+/// `Database` 的对应关系与 `SqlxWhereArgs` 相同。
+pub trait SqlxValuesArgs {
+    /// 绑定参数使用的数据库
+    type Database: Database;
+
+    /// 按照 `generate_values_clause` 生成的占位符顺序绑定字段。
+    ///
+    /// 带序号的占位符按照序号绑定，通过 `index` 重复引用的序号只绑定一次。
+    fn bind_values_args<'q>(
+        &'q self,
+        query: SqlxQuery<'q, Self::Database>,
+    ) -> SqlxQuery<'q, Self::Database>;
+}
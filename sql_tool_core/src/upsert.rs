@@ -0,0 +1,122 @@
+use crate::{Clause, Dialect};
+
+/// `Upsert` 表示一条 upsert 语句的各个部分，由 `GenInsert` 生成的代码构造，再按照数据库渲染。
+///
+/// - Postgres、SQLite：`INSERT ... ON CONFLICT (id) DO UPDATE SET col = EXCLUDED.col`，没有需要更新的字段时为 `DO NOTHING`
+/// - MySql、MariaDB：`INSERT ... ON DUPLICATE KEY UPDATE col = VALUES(col)`，没有需要更新的字段时为 `id = id`
+/// - MsSql：`MERGE INTO ... USING (VALUES (...)) AS source (...) ON ... WHEN MATCHED ... WHEN NOT MATCHED ...;`
+///
+/// # 示例
+/// ```
+/// use sql_tool_core::{Clause, Dialect, SqlFragment, Upsert};
+///
+/// let columns: Clause = vec![SqlFragment::new("id", "id"), SqlFragment::new("title", "title")]
+///     .into_iter()
+///     .collect();
+/// let values: Clause = vec![SqlFragment::new("$1", "id"), SqlFragment::new("$2", "title")]
+///     .into_iter()
+///     .collect();
+/// let upsert = Upsert {
+///     table: "advert",
+///     columns: &columns,
+///     values: &values,
+///     conflict: &["id".to_string()],
+///     update: &["title"],
+/// };
+/// assert_eq!(
+///     upsert.to_sql(Dialect::Postgres),
+///     "INSERT INTO advert (id, title) VALUES ($1, $2) ON CONFLICT (id) DO UPDATE SET title = EXCLUDED.title"
+/// );
+/// assert_eq!(
+///     upsert.to_sql(Dialect::MySql),
+///     "INSERT INTO advert (id, title) VALUES ($1, $2) ON DUPLICATE KEY UPDATE title = VALUES(title)"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Upsert<'a> {
+    /// 表名
+    pub table: &'a str,
+    /// 插入的字段名
+    pub columns: &'a Clause,
+    /// 插入的值，与 `columns` 一一对应
+    pub values: &'a Clause,
+    /// `#[config(conflict = [...])]` 设置的冲突字段，至少有一个
+    pub conflict: &'a [String],
+    /// `#[value(conflict_update)]` 标记的、发生冲突时需要更新的字段名
+    pub update: &'a [&'a str],
+}
+
+impl Upsert<'_> {
+    /// 按照 `dialect` 生成完整的 upsert 语句
+    pub fn to_sql(&self, dialect: Dialect) -> String {
+        let columns = self.columns.join(", ");
+        let values = self.values.join(", ");
+        match dialect {
+            Dialect::Postgres | Dialect::Sqlite => {
+                let action = if self.update.is_empty() {
+                    "DO NOTHING".to_string()
+                } else {
+                    format!(
+                        "DO UPDATE SET {}",
+                        self.assignments(|name| format!("EXCLUDED.{}", name))
+                    )
+                };
+                format!(
+                    "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) {}",
+                    self.table,
+                    columns,
+                    values,
+                    self.conflict.join(", "),
+                    action
+                )
+            }
+            Dialect::MySql | Dialect::MariaDb => {
+                let assignments = if self.update.is_empty() {
+                    // 没有需要更新的字段时将冲突字段更新为自身，相当于忽略冲突的行
+                    format!("{} = {}", self.conflict[0], self.conflict[0])
+                } else {
+                    self.assignments(|name| format!("VALUES({})", name))
+                };
+                format!(
+                    "INSERT INTO {} ({}) VALUES ({}) ON DUPLICATE KEY UPDATE {}",
+                    self.table, columns, values, assignments
+                )
+            }
+            Dialect::MsSql => {
+                let on = self
+                    .conflict
+                    .iter()
+                    .map(|name| format!("target.{} = source.{}", name, name))
+                    .collect::<Vec<_>>()
+                    .join(" AND ");
+                let matched = if self.update.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        " WHEN MATCHED THEN UPDATE SET {}",
+                        self.assignments(|name| format!("source.{}", name))
+                    )
+                };
+                let source = self
+                    .columns
+                    .iter()
+                    .map(|column| format!("source.{}", column))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "MERGE INTO {} AS target USING (VALUES ({})) AS source ({}) ON {}{} WHEN NOT MATCHED THEN INSERT ({}) VALUES ({});",
+                    self.table, values, columns, on, matched, columns, source
+                )
+            }
+        }
+    }
+
+    /// 生成 `col = value` 形式的赋值列表，`value` 根据字段名生成赋值的表达式
+    fn assignments(&self, value: impl Fn(&str) -> String) -> String {
+        self.update
+            .iter()
+            .map(|name| format!("{} = {}", name, value(name)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
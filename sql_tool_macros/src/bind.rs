@@ -0,0 +1,605 @@
+use std::collections::BTreeMap;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use sql_tool_core::Dialect;
+use syn::{DeriveInput, Ident};
+
+use crate::macro_utils::{core_path, dialect_tokens, Errors};
+
+/// 生成了占位符、需要绑定参数的字段
+#[derive(Clone)]
+pub struct BindField {
+    /// 结构体字段
+    pub ident: Ident,
+    /// `ignore_none` 生效的 `Option` 字段，为 `None` 时不会生成占位符
+    pub optional: bool,
+    /// 占位符的序号，为 `None` 时使用全局的 index
+    pub index: Option<usize>,
+    /// 生成之后全局的 index 增加的值
+    pub add_index: usize,
+    /// 模板中占位符出现的次数
+    pub count: usize,
+    /// 绑定时使用的值
+    pub value: BindValue,
+}
+
+impl BindField {
+    /// 生成运行时重新编号时渲染该字段的代码，`block` 为渲染该字段的代码块。
+    ///
+    /// 渲染之前将作用域中的 `index` 设置为重新编号之后的序号，
+    /// 依赖 `BindArgs::slots_tokens` 生成的 `slots` 和 `slot` 变量。
+    pub fn renumber_tokens(&self, block: TokenStream) -> TokenStream {
+        match self.index {
+            Some(field_index) => quote! {{
+                index = slots.get(#field_index);
+                #block
+            }},
+            None => {
+                let add_index = self.add_index;
+                quote! {{
+                    index = slots.get(slot);
+                    #block
+                    slot += #add_index;
+                }}
+            }
+        }
+    }
+}
+
+/// 绑定字段时使用的值
+#[derive(Clone)]
+pub enum BindValue {
+    /// 字段的引用
+    Field,
+    /// `#[r#where(in)]` 字段在运行时返回元素切片的表达式，每个元素生成一个占位符并依次绑定
+    List(TokenStream),
+    /// `#[r#where(like = "...")]` 字段的匹配方式，如 `LikeMode::Contains`，绑定转义并加上 `%` 之后的值
+    Like(TokenStream),
+    /// `#[r#where(any)]` 字段在运行时返回元素切片的表达式，
+    /// 运行时的数据库为 postgres 时将整个字段作为一个数组绑定，其余数据库与 `List` 相同
+    Any(TokenStream),
+    /// `#[r#where(range)]` 字段，存在的边界依次生成一个占位符并绑定
+    Range,
+    /// `Option<Option<T>>` 字段，只有 `Some(Some(value))` 时生成占位符并绑定 `value`
+    Nullable,
+}
+
+/// 生成绑定参数的代码，供 `GenWhere`、`GenSet` 和 `GenValues` 共用
+pub struct BindArgs {
+    /// `#[config(database = "...")]` 设置的数据库
+    dialect: Dialect,
+    /// 占位符的起始索引
+    index: usize,
+    /// 按照生成顺序排列的字段
+    fields: Vec<BindField>,
+    /// 所有字段都生成占位符时，每个字段在编译期的占位符序号
+    slots: Vec<usize>,
+    /// 每个字段是否通过 `index` 引用了其他字段生成的占位符
+    reused: Vec<bool>,
+    /// 是否在运行时通过 `SlotMap` 重新编号
+    renumber: bool,
+    /// 所有字段都生成占位符时，每个占位符序号所属的字段，按照序号排序
+    map: Vec<(usize, String)>,
+}
+
+impl BindArgs {
+    /// 在编译期计算每个占位符序号所属的字段，供每个字段总是生成占位符的 `GenValues` 使用。
+    ///
+    /// 没有设置 `index` 的字段按照顺序占用全局的 index，设置了 `index` 的字段：
+    /// - 序号已经被其他字段占用时，视为引用该字段的占位符，不需要再次绑定
+    /// - 序号没有被占用时，该序号属于这个字段，多个不同的字段使用同一个这样的序号时返回错误
+    ///
+    /// `postgres` 的占位符序号从起始索引开始必须是连续的，存在空缺时返回错误。
+    pub fn new(dialect: Dialect, index: usize, fields: Vec<BindField>) -> syn::Result<Self> {
+        Self::build(dialect, index, fields, false)
+    }
+
+    /// 与 `new` 相同，供字段不一定生成占位符的 `GenWhere` 和 `GenSet` 使用。
+    ///
+    /// 存在设置了 `index` 的字段时，生成的代码在运行时通过 `SlotMap` 将编译期的序号重新编号，
+    /// 跳过的字段以及序号的空缺都不会出现在生成的占位符中，因此不检查 `postgres` 的序号是否连续。
+    pub fn renumbered(dialect: Dialect, index: usize, fields: Vec<BindField>) -> syn::Result<Self> {
+        let renumber = fields.iter().any(|field| field.index.is_some());
+        Self::build(dialect, index, fields, renumber)
+    }
+
+    fn build(
+        dialect: Dialect,
+        index: usize,
+        fields: Vec<BindField>,
+        renumber: bool,
+    ) -> syn::Result<Self> {
+        let mut errors = Errors::default();
+        let mut owners: BTreeMap<usize, &Ident> = BTreeMap::new();
+        let mut slots = Vec::with_capacity(fields.len());
+        let mut next = index;
+        for field in &fields {
+            match field.index {
+                Some(field_index) => slots.push(field_index),
+                None => {
+                    owners.entry(next).or_insert(&field.ident);
+                    slots.push(next);
+                    next += field.add_index;
+                }
+            }
+        }
+
+        let mut reused = Vec::with_capacity(fields.len());
+        let mut claims: BTreeMap<usize, &Ident> = BTreeMap::new();
+        for field in &fields {
+            let Some(field_index) = field.index else {
+                reused.push(false);
+                continue;
+            };
+            if owners.contains_key(&field_index) {
+                reused.push(true);
+                continue;
+            }
+            reused.push(false);
+            match claims.get(&field_index) {
+                Some(owner) if *owner != &field.ident => {
+                    errors.push_spanned(
+                        &field.ident,
+                        format!(
+                            "字段 `{}` 与字段 `{}` 都使用了序号为 {} 的占位符，请检查 `index` 是否正确",
+                            field.ident, owner, field_index
+                        ),
+                    );
+                }
+                _ => {
+                    claims.insert(field_index, &field.ident);
+                }
+            }
+        }
+        owners.extend(claims);
+
+        if dialect == Dialect::Postgres && !renumber {
+            let mut previous = index;
+            for (&slot, owner) in owners.range(index..) {
+                if slot > previous {
+                    errors.push_spanned(
+                        owner,
+                        format!(
+                            "postgres 的占位符序号不连续，缺少 ${}，请检查 `index` 是否正确",
+                            previous
+                        ),
+                    );
+                }
+                previous = slot + 1;
+            }
+        }
+        errors.finish()?;
+
+        // 重新编号之后，起始索引之后的序号按照顺序依次排列
+        let below = owners.range(..index).count();
+        let map = owners
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (slot, ident))| {
+                let slot = if renumber && slot >= index {
+                    index + rank - below
+                } else {
+                    slot
+                };
+                (slot, ident.to_string())
+            })
+            .collect();
+        Ok(BindArgs {
+            dialect,
+            index,
+            fields,
+            slots,
+            reused,
+            renumber,
+            map,
+        })
+    }
+
+    /// 生成占位符序号与字段名对应关系的代码，返回 `&'static [(usize, &'static str)]`
+    pub fn map_tokens(&self) -> TokenStream {
+        let map = self
+            .map
+            .iter()
+            .map(|(slot, field)| quote! { (#slot, #field) });
+        quote! { &[#(#map),*] }
+    }
+
+    /// 生成运行时重新编号需要的 `slots` 和 `slot` 变量，见 `slots_tokens_for`
+    pub fn slots_tokens(&self) -> TokenStream {
+        self.slots_tokens_for(self.fields.len())
+    }
+
+    /// 生成运行时重新编号需要的变量，只有前 `count` 个字段参与生成时使用。
+    ///
+    /// `slots: SlotMap` 记录实际生成了占位符的字段在编译期的序号，`slot` 为下一个没有设置 `index` 的字段在编译期的序号。
+    /// 生成的代码依赖作用域中的 `index` 变量作为运行时的起始索引，不需要重新编号时不生成任何代码。
+    pub fn slots_tokens_for(&self, count: usize) -> TokenStream {
+        if !self.renumber {
+            return quote! {};
+        }
+        let core = core_path();
+        let base = self.index;
+        let fields = &self.fields[..count];
+        let push = fields.iter().zip(&self.slots).map(|(field, slot)| {
+            let ident = &field.ident;
+            match field.value {
+                BindValue::Nullable => quote! {
+                    if let Some(Some(_)) = &self.#ident {
+                        slots.push(#slot);
+                    }
+                },
+                _ if field.optional => quote! {
+                    if self.#ident.is_some() {
+                        slots.push(#slot);
+                    }
+                },
+                _ => quote! { slots.push(#slot); },
+            }
+        });
+        let slot = fields
+            .iter()
+            .any(|field| field.index.is_none())
+            .then(|| quote! { let mut slot = #base; });
+        quote! {
+            let slots = {
+                let mut slots = #core::SlotMap::new(#base, index);
+                #(#push)*
+                slots
+            };
+            #slot
+        }
+    }
+
+    /// 生成重新编号之后将 `index` 设置为下一个可用序号的代码，不需要重新编号时不生成任何代码
+    pub fn next_index_tokens(&self) -> TokenStream {
+        if self.renumber {
+            quote! { index = slots.next_index(); }
+        } else {
+            quote! {}
+        }
+    }
+
+    /// 生成按照占位符的顺序返回 `Vec<SqlArg>` 的代码，依赖作用域中的 `dialect` 变量
+    pub fn args_tokens(&self) -> TokenStream {
+        let core = core_path();
+        let push = self.for_each_field(
+            quote! { args.push(#core::SqlArg::Ref(field)); },
+            Some(quote! { args.push(#core::SqlArg::Owned(Box::new(field))); }),
+            true,
+        );
+        quote! {{
+            let mut args: Vec<#core::SqlArg<'_>> = Vec::new();
+            #push
+            args
+        }}
+    }
+
+    /// 生成按照占位符的顺序依次取得字段的代码，`bind` 将字段的引用转换为绑定参数的表达式。
+    ///
+    /// 生成的代码依赖作用域中的 `dialect` 变量，
+    /// 其中的 `for` 循环每次循环中 `field` 为需要绑定的字段的引用。
+    ///
+    /// `like` 字段绑定转义之后的值：`bind_owned` 为 `Some` 时 `field` 为该值本身，
+    /// 否则 `field` 为 `&LikeParam`，在转换为数据库参数时才生成该值。
+    ///
+    /// `runtime_dialect` 表示 `dialect` 在运行时才能确定，为 `false` 时 `dialect` 总是结构体上设置的数据库，
+    /// `any` 字段只会绑定整个字段，不要求元素的类型同样可以绑定。
+    fn for_each_field(
+        &self,
+        bind: TokenStream,
+        bind_owned: Option<TokenStream>,
+        runtime_dialect: bool,
+    ) -> TokenStream {
+        let core = core_path();
+        let index = self.index;
+        let plan =
+            self.fields
+                .iter()
+                .zip(&self.reused)
+                .enumerate()
+                .map(|(position, (field, reused))| {
+                    let BindField {
+                        ident,
+                        optional,
+                        index,
+                        add_index,
+                        count,
+                        value,
+                    } = field;
+                    // 重新编号时使用编译期的序号排序，与运行时的序号顺序一致
+                    let index = match index {
+                        Some(index) => quote! { #index },
+                        None if self.renumber => {
+                            let slot = self.slots[position];
+                            quote! { #slot }
+                        }
+                        None => quote! { index },
+                    };
+                    let push = if *reused {
+                        quote! { plan.push_reused(#index, #position, #count); }
+                    } else {
+                        quote! { plan.push(#index, #position, #count); }
+                    };
+                    let push = match value {
+                        BindValue::List(list) => quote! {
+                            for _ in #list {
+                                plan.push(index, #position, 1);
+                                index += 1;
+                            }
+                        },
+                        BindValue::Any(list) => quote! {
+                            if dialect == #core::Dialect::Postgres {
+                                #push
+                                index += #add_index;
+                            } else {
+                                for _ in #list {
+                                    plan.push(index, #position, 1);
+                                    index += 1;
+                                }
+                            }
+                        },
+                        BindValue::Range => quote! {
+                            let (start, end) = #core::RangeValue::bounds(&self.#ident);
+                            for _ in [start, end].into_iter().flatten() {
+                                plan.push(index, #position, 1);
+                                index += 1;
+                            }
+                        },
+                        BindValue::Nullable => quote! {
+                            if let Some(Some(_)) = &self.#ident {
+                                #push
+                                index += #add_index;
+                            }
+                        },
+                        BindValue::Field | BindValue::Like(_) => quote! {
+                            #push
+                            index += #add_index;
+                        },
+                    };
+                    if *optional {
+                        quote! {
+                            if self.#ident.is_some() {
+                                #push
+                            }
+                        }
+                    } else {
+                        quote! {{ #push }}
+                    }
+                });
+        // 列表和范围字段按照元素的顺序依次绑定，每个字段使用一个计数器记录下一个元素
+        let elements: Vec<_> = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| match field.value {
+                BindValue::List(_) | BindValue::Range => true,
+                BindValue::Any(_) => runtime_dialect,
+                _ => false,
+            })
+            .map(|(position, _)| format_ident!("element_{}", position))
+            .collect();
+        let arms = self.fields.iter().enumerate().map(|(position, field)| {
+            let ident = &field.ident;
+            match &field.value {
+                BindValue::List(list) => {
+                    let element = format_ident!("element_{}", position);
+                    quote! {
+                        #position => {
+                            let field = &(#list)[#element];
+                            #element += 1;
+                            #bind
+                        }
+                    }
+                }
+                BindValue::Any(list) if runtime_dialect => {
+                    let element = format_ident!("element_{}", position);
+                    quote! {
+                        #position => {
+                            if dialect == #core::Dialect::Postgres {
+                                let field = &self.#ident;
+                                #bind
+                            } else {
+                                let field = &(#list)[#element];
+                                #element += 1;
+                                #bind
+                            }
+                        }
+                    }
+                }
+                BindValue::Range => {
+                    let element = format_ident!("element_{}", position);
+                    quote! {
+                        #position => {
+                            let (start, end) = #core::RangeValue::bounds(&self.#ident);
+                            if let Some(field) = [start, end].into_iter().flatten().nth(#element) {
+                                #bind
+                            }
+                            #element += 1;
+                        }
+                    }
+                }
+                BindValue::Like(mode) => match &bind_owned {
+                    Some(bind_owned) => quote! {
+                        #position => {
+                            let field = #core::LikeValue::to_pattern(&self.#ident, #mode, dialect);
+                            #bind_owned
+                        }
+                    },
+                    None => quote! {
+                        #position => {
+                            let field = #core::LikeParam::<_, { #mode as u8 }>::from_ref(&self.#ident);
+                            #bind
+                        }
+                    },
+                },
+                BindValue::Nullable => quote! {
+                    #position => {
+                        if let Some(Some(field)) = &self.#ident {
+                            #bind
+                        }
+                    }
+                },
+                BindValue::Field | BindValue::Any(_) => {
+                    quote! { #position => { let field = &self.#ident; #bind } }
+                }
+            }
+        });
+
+        quote! {
+            let mut plan = #core::BindPlan::new(dialect);
+            let mut index = #index;
+            #(#plan)*
+            #(let mut #elements = 0;)*
+            for position in plan.fields() {
+                match position {
+                    #(#arms)*
+                    _ => unreachable!("BindPlan 只会返回已记录的字段"),
+                }
+            }
+        }
+    }
+
+    /// 生成 `tokio-postgres`/`postgres` 的参数方法，`trait_name` 为 `PgWhereParams` 等。
+    ///
+    /// 只有 `database = "postgres"` 时生成代码。
+    pub fn postgres_impl(&self, input: &DeriveInput, trait_name: &str) -> TokenStream {
+        let core = core_path();
+        if !cfg!(feature = "postgres") || self.dialect != Dialect::Postgres {
+            return quote! {};
+        }
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let trait_name = Ident::new(trait_name, proc_macro2::Span::call_site());
+        let dialect = dialect_tokens(self.dialect);
+        let push = self.for_each_field(quote! { params.push(field); }, None, false);
+
+        quote! {
+            impl #impl_generics #core::#trait_name for #name #ty_generics #where_clause {
+                fn to_pg_params(&self) -> Vec<#core::PgParam<'_>> {
+                    let dialect = #dialect;
+                    let mut params: Vec<#core::PgParam<'_>> = Vec::new();
+                    #push
+                    params
+                }
+            }
+        }
+    }
+
+    /// 生成 `rusqlite` 的参数方法，`trait_name` 为 `RusqliteWhereParams` 等。
+    ///
+    /// `rusqlite` 只支持 SQLite，`database` 不是 `sqlite` 时不生成任何代码。
+    pub fn rusqlite_impl(&self, input: &DeriveInput, trait_name: &str) -> TokenStream {
+        if !cfg!(feature = "rusqlite") || self.dialect != Dialect::Sqlite {
+            return quote! {};
+        }
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let trait_name = Ident::new(trait_name, proc_macro2::Span::call_site());
+        let dialect = dialect_tokens(self.dialect);
+        let push = self.for_each_field(quote! { params.push(field); }, None, false);
+        let core = core_path();
+
+        quote! {
+            impl #impl_generics #core::#trait_name for #name #ty_generics #where_clause {
+                fn to_rusqlite_params(&self) -> Vec<&dyn ::rusqlite::ToSql> {
+                    let dialect = #dialect;
+                    let mut params: Vec<&dyn ::rusqlite::ToSql> = Vec::new();
+                    #push
+                    params
+                }
+            }
+        }
+    }
+
+    /// 生成 `sqlx` 的绑定方法，`trait_name` 和 `method` 为 `SqlxWhereArgs::bind_where_args` 等。
+    ///
+    /// `sqlx` 不支持 `mssql`，此时不生成任何代码。
+    pub fn sqlx_impl(&self, input: &DeriveInput, trait_name: &str, method: &str) -> TokenStream {
+        let core = core_path();
+        if !cfg!(feature = "sqlx") {
+            return quote! {};
+        }
+        let database = match self.dialect {
+            Dialect::Postgres => quote! { ::sqlx::Postgres },
+            Dialect::MySql | Dialect::MariaDb => quote! { ::sqlx::MySql },
+            Dialect::Sqlite => quote! { ::sqlx::Sqlite },
+            Dialect::MsSql => return quote! {},
+        };
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let trait_name = Ident::new(trait_name, proc_macro2::Span::call_site());
+        let method = Ident::new(method, proc_macro2::Span::call_site());
+        let dialect = dialect_tokens(self.dialect);
+        let bind = quote! { query = query.bind(field); };
+        let bind = self.for_each_field(bind.clone(), Some(bind), false);
+
+        quote! {
+            impl #impl_generics #core::#trait_name for #name #ty_generics #where_clause {
+                type Database = #database;
+
+                fn #method<'q>(
+                    &'q self,
+                    query: #core::SqlxQuery<'q, Self::Database>,
+                ) -> #core::SqlxQuery<'q, Self::Database> {
+                    let dialect = #dialect;
+                    let mut query = query;
+                    #bind
+                    query
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, optional: bool, index: Option<usize>) -> BindField {
+        BindField {
+            ident: Ident::new(name, proc_macro2::Span::call_site()),
+            optional,
+            index,
+            add_index: 1,
+            count: 1,
+            value: BindValue::Field,
+        }
+    }
+
+    #[test]
+    fn index_test() {
+        // `a` 为 `None` 时在运行时重新编号，`b` 仍然可以引用 `$1`
+        let fields = vec![
+            field("a", true, None),
+            field("b", false, Some(1)),
+            field("c", false, None),
+        ];
+        let binds = BindArgs::renumbered(Dialect::Postgres, 1, fields).unwrap();
+        assert_eq!(binds.map, vec![(1, "a".to_string()), (2, "c".to_string())]);
+
+        // 重新编号时序号的空缺会被压缩，`GenValues` 中则返回错误
+        let fields = vec![field("id", false, None), field("title", false, Some(3))];
+        let binds = BindArgs::renumbered(Dialect::Postgres, 1, fields).unwrap();
+        assert_eq!(
+            binds.map,
+            vec![(1, "id".to_string()), (2, "title".to_string())]
+        );
+        let fields = vec![field("id", false, None), field("title", false, Some(3))];
+        assert!(BindArgs::new(Dialect::Postgres, 1, fields).is_err());
+        let fields = vec![field("id", false, None), field("title", false, Some(3))];
+        assert!(BindArgs::new(Dialect::MySql, 1, fields).is_ok());
+
+        // 所有冲突都会一起返回
+        let fields = vec![
+            field("a", false, Some(2)),
+            field("b", false, Some(2)),
+            field("c", false, Some(3)),
+            field("d", false, Some(3)),
+        ];
+        let error = BindArgs::renumbered(Dialect::Postgres, 1, fields)
+            .err()
+            .unwrap();
+        assert_eq!(error.into_iter().count(), 2);
+    }
+}
@@ -0,0 +1,108 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+use crate::macro_utils::{
+    config_bool, config_table, core_path, dialect_tokens, into_token_stream, Errors,
+};
+use crate::where_macro::{where_clause, WhereClause};
+
+/// 生成针对特定结构体的 `DeleteAttributeMacro` 实现。
+///
+/// `WHERE` 部分复用 `#[r#where(...)]` 的解析规则，表名来自结构体上的 `#[config(table = "...")]`。
+pub fn gen_delete_attribute_impl(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    into_token_stream(expand(&input))
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
+    let name = &input.ident;
+    let mut errors = Errors::default();
+
+    let table = match errors.handle(config_table(input)) {
+        Some(Some(table)) => Some(table),
+        Some(None) => {
+            errors.push_spanned(
+                name,
+                "必须在结构上设置 `#[config(table = \"/*表名*/\")]` 宏",
+            );
+            None
+        }
+        None => None,
+    };
+    let allow_full_delete = errors
+        .handle(config_bool(input, "allow_full_delete"))
+        .unwrap_or_default();
+    let where_data = errors.handle(where_clause(input));
+    errors.finish()?;
+
+    let (
+        Some(table),
+        Some(WhereClause {
+            dialect,
+            index,
+            values,
+            binds,
+            ..
+        }),
+    ) = (table, where_data)
+    else {
+        unreachable!("存在错误时已经提前返回");
+    };
+    let ordered_args = binds.args_tokens();
+    let slots = binds.slots_tokens();
+    let dialect = dialect_tokens(dialect);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let prefix = format!("DELETE FROM {}", table);
+    // 没有显式允许时，拒绝生成没有 WHERE 条件的 DELETE 语句
+    let check_where = if allow_full_delete {
+        quote! {}
+    } else {
+        quote! {
+            if fields.is_empty() {
+                return Err(#core::SqlToolError::MissingWhere);
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #core::DeleteAttributeMacro for #name #ty_generics #where_clause {
+            fn generate_delete_sql(&self) -> Result<String, #core::SqlToolError> {
+                self.generate_delete_sql_for(#dialect)
+            }
+            fn generate_delete_sql_for(&self, dialect: #core::Dialect) -> Result<String, #core::SqlToolError> {
+                self.generate_delete_sql_with_args_for(dialect)
+                    .map(|(sql, _)| sql)
+            }
+            fn generate_delete_sql_with_args(
+                &self,
+            ) -> Result<(String, Vec<#core::SqlArg<'_>>), #core::SqlToolError> {
+                self.generate_delete_sql_with_args_for(#dialect)
+            }
+            fn generate_delete_sql_with_args_for(
+                &self,
+                dialect: #core::Dialect,
+            ) -> Result<(String, Vec<#core::SqlArg<'_>>), #core::SqlToolError> {
+                let mut fields = #core::Clause::new();
+                let mut args: Vec<#core::SqlArg<'_>> = Vec::new();
+                let mut index = #index;
+                // DELETE 语句没有表别名
+                let alias: Option<&str> = None;
+                #slots
+                #(#values)*
+                #check_where
+                let mut sql = #prefix.to_string();
+                if !fields.is_empty() {
+                    sql.push_str(" WHERE ");
+                    sql.push_str(&fields.join(" AND "));
+                }
+                // 带序号的占位符按照序号排列参数
+                Ok((sql, #ordered_args))
+            }
+        }
+    })
+}
@@ -2,9 +2,15 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Expr, Lit, Meta, Token};
+use sql_tool_core::Dialect;
+use syn::{parse_macro_input, DeriveInput};
 
-use syn::punctuated::Punctuated;
+use crate::macro_utils::{
+    config_quote, core_path, dialect_tokens, field_ident, find_attribute, fragment_tokens,
+    into_token_stream, is_plain_identifier, meta_name, name_value_to_bool, name_value_to_string,
+    named_fields, parse_nested, quote_column, require_name_value, require_path, unknown_meta,
+    ColumnName, Errors,
+};
 
 /// 生成针对特定结构体的 `FieldsAttributeMacro` 实现。
 ///
@@ -21,59 +27,134 @@ use syn::punctuated::Punctuated;
 /// 此函数将为 `MyStruct` 生成相应的 `FieldsAttributeMacro` 实现。
 pub fn gen_fields_attribute_impl(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    let name = &input.ident;
-    let fields = if let Data::Struct(data_struct) = &input.data {
-        let mut fields = Vec::new();
-        for field in &data_struct.fields {
-            let mut field_name = Some(field.ident.as_ref().unwrap().to_string());
-            let attrs = field
-                .attrs
-                .iter()
-                .find(|attr| attr.path().is_ident("field"));
-
-            if let Some(attr) = attrs {
-                let nested = attr
-                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-                    .map_err(|e| {
-                        println!("分析 'field' 属性时出错");
-                        e
-                    })
-                    .unwrap();
-
-                for meta in nested {
-                    match meta {
-                        Meta::Path(_) if meta.path().is_ident("ignore") => {
+    into_token_stream(expand(&input))
+}
+
+/// `#[field(...)]` 解析后的结果，供 `GenFields` 和 `GenInsert` 共用
+pub struct FieldsClause {
+    /// `#[config(database = "...")]` 设置的数据库，未设置时为 `Dialect::Postgres`
+    pub dialect: Dialect,
+    /// 每个字段在 SQL 中的名称
+    pub fields: Vec<FieldColumn>,
+}
+
+/// 结构体字段在 SQL 中对应的名称
+pub struct FieldColumn {
+    /// 结构体字段名
+    pub field: String,
+    /// 字段名称或 `rename` 指定的名称
+    pub name: String,
+    /// 是否需要使用引号包裹
+    pub quote: bool,
+}
+
+impl FieldsClause {
+    /// 生成在运行时使用 `dialect` 变量渲染所有字段名的代码
+    pub fn render_tokens(&self) -> Vec<proc_macro2::TokenStream> {
+        render_names(&self.fields, false)
+    }
+}
+
+/// 生成渲染字段名的代码，每个字段返回一个 `SqlFragment`。
+///
+/// `qualify` 为 `true` 时普通的字段名会加上作用域中 `alias` 表示的表别名，
+/// `rename` 为表达式的字段会被标记为表达式。
+pub fn render_names(fields: &[FieldColumn], qualify: bool) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .map(|column| {
+            let render = ColumnName::new(&column.name, column.quote, qualify).render();
+            let expression = !is_plain_identifier(&column.name);
+            fragment_tokens(render, &column.field, None, expression)
+        })
+        .collect()
+}
+
+/// 解析结构体上的 `#[config(...)]` 以及字段上的 `#[field(...)]`
+pub fn fields_clause(input: &DeriveInput) -> syn::Result<FieldsClause> {
+    let mut errors = Errors::default();
+
+    let mut fields = Vec::new();
+    for field in named_fields(input)? {
+        let field_value = field_ident(field).to_string();
+        let mut field_name = Some(field_value.clone());
+        let mut quote = None;
+        let attrs = find_attribute(&field.attrs, "field");
+
+        if let Some(attr) = attrs {
+            let Some(nested) = errors.handle(parse_nested(attr)) else {
+                continue;
+            };
+
+            for meta in nested {
+                match meta_name(&meta).as_str() {
+                    "ignore" => {
+                        if errors.handle(require_path(&meta)).is_some() {
                             field_name = None;
-                            break;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("rename") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Str(val) = &value.lit {
-                                    field_name = Some(val.value());
-                                }
+                    }
+                    "rename" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            if field_name.is_some() {
+                                field_name = Some(value);
                             }
                         }
-                        _ => {}
                     }
+                    "quote" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                        {
+                            quote = Some(value);
+                        }
+                    }
+                    _ => errors.push(unknown_meta(&meta, "field")),
                 }
             }
-            if let Some(value) = field_name {
-                fields.push(value);
-            }
         }
+        if let Some(value) = field_name {
+            fields.push((field_value, value, quote));
+        }
+    }
+    let needs_dialect = fields.iter().any(|(_, _, quote)| *quote == Some(true));
+    let config = errors.handle(config_quote(input, needs_dialect));
+    errors.finish()?;
 
-        fields
-    } else {
-        Vec::new()
+    let Some((dialect, quote_identifiers)) = config else {
+        unreachable!("存在错误时已经提前返回");
     };
+    let fields = fields
+        .into_iter()
+        .map(|(field, name, quote)| FieldColumn {
+            quote: quote_column(quote, quote_identifiers, &name),
+            field,
+            name,
+        })
+        .collect();
+
+    Ok(FieldsClause { dialect, fields })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
+    let name = &input.ident;
+    let fields_clause = fields_clause(input)?;
+    let dialect = dialect_tokens(fields_clause.dialect);
+    let fields = fields_clause.render_tokens();
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    let expanded = quote! {
-        impl FieldsAttributeMacro for #name {
-            fn generate_fields_clause() -> Vec<String> {
-                vec![#(#fields.to_string()),*]
+    Ok(quote! {
+        impl #impl_generics #core::FieldsAttributeMacro for #name #ty_generics #where_clause {
+            fn generate_fields_fragments() -> #core::Clause {
+                Self::generate_fields_fragments_for(#dialect)
+            }
+            fn generate_fields_fragments_for(dialect: #core::Dialect) -> #core::Clause {
+                let mut fields = #core::Clause::new();
+                #(fields.push(#fields);)*
+                fields
             }
         }
-    };
-
-    TokenStream::from(expanded)
+    })
 }
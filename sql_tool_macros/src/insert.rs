@@ -0,0 +1,247 @@
+extern crate proc_macro;
+use proc_macro::TokenStream;
+
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+use crate::fields::fields_clause;
+use crate::macro_utils::{
+    config_bool, config_conflict, config_table, core_path, dialect_tokens, field_ident,
+    find_attribute, into_token_stream, meta_name, named_fields, parse_nested, quote_column,
+    ColumnName, Errors,
+};
+use crate::values::values_clause;
+
+/// 生成针对特定结构体的 `InsertAttributeMacro` 实现。
+///
+/// 字段列表复用 `#[field(...)]` 的解析规则，值列表复用 `#[value(...)]` 的解析规则，
+/// 表名来自结构体上的 `#[config(table = "...")]`。
+/// 字段只被 `#[field(ignore)]` 或 `#[value(ignore)]` 其中之一忽略时会产生编译错误。
+///
+/// 设置了 `#[config(conflict = ["id", ...])]` 时，`generate_upsert_sql` 生成 upsert 语句，
+/// 冲突字段必须是插入的字段之一，`#[value(conflict_update)]` 标记发生冲突时需要更新的字段，不能用于冲突字段。
+pub fn gen_insert_attribute_impl(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    into_token_stream(expand(&input))
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
+    let name = &input.ident;
+    let mut errors = Errors::default();
+
+    let table = match errors.handle(config_table(input)) {
+        Some(Some(table)) => Some(table),
+        Some(None) => {
+            errors.push_spanned(
+                name,
+                "必须在结构上设置 `#[config(table = \"/*表名*/\")]` 宏",
+            );
+            None
+        }
+        None => None,
+    };
+    let fields = errors.handle(fields_clause(input));
+    let values = errors.handle(values_clause(input));
+    let conflict = errors.handle(config_conflict(input));
+    let quote_identifiers = errors.handle(config_bool(input, "quote_identifiers"));
+    errors.finish()?;
+
+    let (Some(table), Some(fields), Some(values), Some(conflict), Some(quote_identifiers)) =
+        (table, fields, values, conflict, quote_identifiers)
+    else {
+        unreachable!("存在错误时已经提前返回");
+    };
+    // 字段列表与值列表都按照结构体字段的顺序生成，两者忽略的字段相同时才能一一对应
+    let mut errors = Errors::default();
+    for field in named_fields(input)? {
+        let in_fields = !is_ignored(field, "field");
+        let in_values = !is_ignored(field, "value");
+        if in_fields != in_values {
+            let (ignored, kept) = if in_fields {
+                ("#[value(ignore)]", "#[field(ignore)]")
+            } else {
+                ("#[field(ignore)]", "#[value(ignore)]")
+            };
+            let ident = field_ident(field);
+            errors.push_spanned(
+                ident,
+                format!(
+                    "字段 `{}` 设置了 `{}`，但没有设置 `{}`，字段与值无法一一对应",
+                    ident, ignored, kept
+                ),
+            );
+        }
+    }
+    errors.finish()?;
+
+    let update: Vec<usize> = values
+        .values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| value.conflict_update)
+        .map(|(position, _)| position)
+        .collect();
+    let dialect = dialect_tokens(values.dialect);
+    let index = values.index;
+    let render_values = values.render_tokens();
+    let render_fields = fields.render_tokens();
+    // 渲染 `INSERT` 语句的字段部分和值部分，依赖作用域中的 `dialect` 变量
+    let insert_clauses = quote! {{
+        let mut fields = #core::Clause::new();
+        #(fields.push(#render_fields);)*
+        let mut values = #core::Clause::new();
+        let mut index = #index;
+        let mut placeholders = 0;
+        #(#render_values)*
+        (fields, values)
+    }};
+    let upsert = match conflict {
+        Some(conflict) => {
+            let mut errors = Errors::default();
+            for column in &conflict {
+                if !fields
+                    .fields
+                    .iter()
+                    .any(|field| field.name == column.value())
+                {
+                    errors.push_spanned(
+                        column,
+                        format!("冲突字段 `{}` 不在插入的字段中", column.value()),
+                    );
+                }
+            }
+            // 冲突字段用于匹配已经存在的行，不能同时作为需要更新的字段
+            for &position in &update {
+                let column = &fields.fields[position].name;
+                if conflict.iter().any(|conflict| &conflict.value() == column) {
+                    errors.push_spanned(
+                        &values.values[position].ident,
+                        format!(
+                            "字段 `{}` 是冲突字段，不能设置 `#[value(conflict_update)]`",
+                            column
+                        ),
+                    );
+                }
+            }
+            errors.finish()?;
+            let conflict = conflict.iter().map(|column| {
+                let column = column.value();
+                ColumnName::new(
+                    &column,
+                    quote_column(None, quote_identifiers, &column),
+                    false,
+                )
+                .render()
+            });
+            quote! {
+                let conflict: Vec<String> = vec![#(#conflict),*];
+                let (fields, values) = #insert_clauses;
+                let update: Vec<&str> = [#(#update),*]
+                    .iter()
+                    .map(|&position: &usize| fields.fragments()[position].text())
+                    .collect();
+                Ok(#core::Upsert {
+                    table: #table,
+                    columns: &fields,
+                    values: &values,
+                    conflict: &conflict,
+                    update: &update,
+                }
+                .to_sql(dialect))
+            }
+        }
+        None if !update.is_empty() => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "使用 `#[value(conflict_update)]` 时必须设置 `#[config(conflict = [\"/*冲突字段*/\"])]`",
+            ));
+        }
+        None => quote! { Err(#core::SqlToolError::MissingConflict) },
+    };
+
+    let prefix = format!("INSERT INTO {} (", table);
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #core::InsertAttributeMacro for #name #ty_generics #where_clause {
+            fn generate_insert_sql() -> String {
+                Self::generate_insert_sql_for(#dialect)
+            }
+            fn generate_insert_sql_for(dialect: #core::Dialect) -> String {
+                let (fields, values) = #insert_clauses;
+                format!(
+                    "{}{}) VALUES ({})",
+                    #prefix,
+                    fields.join(", "),
+                    values.join(", ")
+                )
+            }
+            fn generate_upsert_sql() -> Result<String, #core::SqlToolError> {
+                Self::generate_upsert_sql_for(#dialect)
+            }
+            fn generate_upsert_sql_for(dialect: #core::Dialect) -> Result<String, #core::SqlToolError> {
+                #upsert
+            }
+        }
+    })
+}
+
+/// 字段是否设置了 `#[attribute(ignore)]`，属性本身的错误由 `fields_clause`、`values_clause` 报告
+fn is_ignored(field: &syn::Field, attribute: &str) -> bool {
+    find_attribute(&field.attrs, attribute)
+        .and_then(|attr| parse_nested(attr).ok())
+        .is_some_and(|nested| nested.iter().any(|meta| meta_name(meta) == "ignore"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatched_ignore_test() {
+        // 字段与值的数量相同，但 `b` 的值会使用 `a` 的占位符
+        let input: DeriveInput = syn::parse_str(
+            r#"#[config(table = "t", database = "postgres")]
+            struct Insert {
+                #[field(ignore)]
+                a: i32,
+                b: i32,
+                #[value(ignore)]
+                c: i32,
+            }"#,
+        )
+        .unwrap();
+        let errors: Vec<String> = expand(&input)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(2, errors.len());
+        assert!(errors[0].starts_with("字段 `a`"));
+        assert!(errors[1].starts_with("字段 `c`"));
+    }
+
+    #[test]
+    fn conflict_update_test() {
+        // MsSql 的 MERGE 不能更新 ON 中使用的冲突字段
+        let input: DeriveInput = syn::parse_str(
+            r#"#[config(table = "t", database = "postgres", conflict = ["id"])]
+            struct Upsert {
+                #[value(conflict_update)]
+                id: i32,
+                #[value(conflict_update)]
+                title: String,
+            }"#,
+        )
+        .unwrap();
+        let errors: Vec<String> = expand(&input)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(1, errors.len());
+        assert!(errors[0].starts_with("字段 `id` 是冲突字段"));
+    }
+}
@@ -1,6 +1,9 @@
 use proc_macro::TokenStream;
 
+mod bind;
+mod delete;
 mod fields;
+mod insert;
 mod macro_utils;
 mod select;
 mod set;
@@ -16,6 +19,10 @@ mod where_macro;
 /// 字段宏参数：
 /// - `ignore` - 忽略该字段
 /// - `rename` - 字段重命名
+/// - `quote` - 是否使用数据库的引号包裹字段名，覆盖结构上的 `quote_identifiers`
+///
+/// 结构宏参数：
+/// - `#[config(quote_identifiers = true, database = "...")]` - 使用数据库的引号包裹所有字段名，如 `"type"`、`` `type` ``、`[type]`
 ///
 /// # 返回值
 /// 返回 TokenStream，包含了生成的 trait 实现代码。
@@ -35,7 +42,7 @@ mod where_macro;
 ///
 /// MyStruct::generate_fields_clause(); // 输出：["field1", "rename_field"]
 /// ```
-#[proc_macro_derive(GenFields, attributes(field))]
+#[proc_macro_derive(GenFields, attributes(field, config))]
 pub fn fields_attribute_macro(item: TokenStream) -> TokenStream {
     fields::gen_fields_attribute_impl(item)
 }
@@ -49,6 +56,13 @@ pub fn fields_attribute_macro(item: TokenStream) -> TokenStream {
 /// 字段宏参数：
 /// - `ignore` - 忽略该字段
 /// - `rename` - 字段重命名
+/// - `quote` - 是否使用数据库的引号包裹字段名，覆盖结构上的 `quote_identifiers`
+///
+/// 结构宏参数：
+/// - `#[config(table = "...")]` - 设置查询的表名，配合 `SelectBuilder` 生成完整的 `SELECT` 语句
+/// - `#[config(quote_identifiers = true, database = "...")]` - 使用数据库的引号包裹所有字段名，`rename` 为表达式时保持原样
+/// - `#[config(alias = "...")]` - 设置表别名，字段名会加上表别名，如 `u.id`，表达式不受影响。
+///   也可以在运行时使用 `generate_select_clause_with_alias("u")` 设置
 ///
 /// # 返回值
 /// 返回 TokenStream，包含了生成的 trait 实现代码。
@@ -70,7 +84,7 @@ pub fn fields_attribute_macro(item: TokenStream) -> TokenStream {
 ///
 /// MyStruct::generate_fields_clause(); // 输出：["field1", "NULL::varchar as city_name", "CASE WHE..."]
 /// ```
-#[proc_macro_derive(GenSelect, attributes(select))]
+#[proc_macro_derive(GenSelect, attributes(select, config))]
 pub fn select_attribute_macro(item: TokenStream) -> TokenStream {
     select::gen_select_attribute_impl(item)
 }
@@ -147,6 +161,7 @@ pub fn values_attribute_macro(item: TokenStream) -> TokenStream {
 ///
 /// 用于生成 SQL `WHERE` 语句部分。此宏依赖于 `WhereAttributeMacro` trait。
 /// 使用方法 `where_data.generate_where_clause()` 会返回一个字段和条件组成的字符串数组。
+/// 使用方法 `where_data.generate_where_clause_with_args()` 会同时返回按占位符顺序排列的参数列表。
 ///
 /// 宏参数：
 /// - `#[config(...)]`: 设置全局配置。
@@ -154,6 +169,8 @@ pub fn values_attribute_macro(item: TokenStream) -> TokenStream {
 ///   - `index`: 设置占位符的起始索引。
 ///   - `ignore_none`: 是否忽略 `Option::None` 值，默认为 `true`。
 ///   - `ignore_no_macro_where`: 是否忽略没有 `#[r#where(...)]` 宏的字段，默认值为 `true`, 为 `true` 时配合 `GenSet` 宏使用。
+///   - `quote_identifiers`: 是否使用数据库的引号包裹 `{name}`，默认为 `false`。
+///   - `alias`: 表别名，设置后 `{name}` 会加上表别名，如 `u.id = $1`，也可以在运行时使用 `generate_where_clause_with_alias("u")` 设置。
 ///
 /// - `#[r#where(...)]`: 字段级别宏，用于自定义字段在 `WHERE` 语句中的表现。
 ///   - `ignore`: 忽略该字段。
@@ -166,12 +183,13 @@ pub fn values_attribute_macro(item: TokenStream) -> TokenStream {
 ///   - `ignore_none`: 当字段为 `Option::None` 时是否忽略，接受布尔类型。
 ///   - `value`: 自定义字段的值，接受字符串类型。
 ///   - `index`: 自定义占位符序号（如果数据库支持），接受整型。
+///   - `quote`: 是否使用数据库的引号包裹 `{name}`，覆盖结构上的 `quote_identifiers`。
 ///
 /// 字段宏属性优先级：
 /// `ignore` > `ignore_none` > `condition_all` > `rename` = `condition` = `value` > `index`
 ///
 /// 示例：
-/// ```rust
+/// ```ignore
 /// #[derive(GenWhere, Debug)]
 /// #[config(database = "postgres")]
 /// pub struct WhereStruct {
@@ -185,6 +203,7 @@ pub fn values_attribute_macro(item: TokenStream) -> TokenStream {
 ///         // ...
 ///     };
 ///     println!("{:?}", data.generate_where_clause());
+///     let (fields, args) = data.generate_where_clause_with_args(); // args 与占位符一一对应
 /// }
 /// ```
 #[proc_macro_derive(GenWhere, attributes(r#where, config))]
@@ -197,6 +216,8 @@ pub fn where_attribute_macro(item: TokenStream) -> TokenStream {
 /// 用于生成 SQL `UPDATE` 语句中的 `SET` 部分。它依赖于 `SetAttributeMacro` trait。
 /// 例如，`update table_name set field1 = $1, field2 = $2 ... where ...`
 /// 使用方法 `update_data.generate_set_clause()` 返回值类似于 `["field1 = $1", "field2 = $2", ...]`。
+/// 设置了 `table` 后，使用方法 `update_data.generate_update_sql()` 返回完整的 `UPDATE` 语句，
+/// 当没有需要更新的字段，或没有任何 `WHERE` 条件（且没有设置 `allow_full_update`）时返回错误。
 ///
 /// 宏参数：
 /// - `#[config(...)]`: 设置一些配置。
@@ -205,6 +226,9 @@ pub fn where_attribute_macro(item: TokenStream) -> TokenStream {
 ///   - `ignore_none`: 是否忽略 `Option::None` 值，默认为 `true`。
 ///   - `ignore_no_macro_set`: 默认忽略没有 `#[set(...)]` 宏的字段，为 `true` 时配合 `GenWhere` 宏使用。
 ///   - `ignore_set_and_where`: 当 `#[set(...)]`存在 `where` 参数是，会忽略 `set` 值，默认为 `false`
+///   - `table`: 表名，设置后可以通过 `generate_update_sql()` 生成完整的 `UPDATE` 语句。
+///   - `allow_full_update`: 是否允许生成没有 `WHERE` 条件的 `UPDATE` 语句，默认为 `false`。
+///   - `quote_identifiers`: 是否使用数据库的引号包裹 `{name}`，默认为 `false`。
 ///
 /// - `#[set(...)]`: 字段级别的宏，用于自定义字段在生成的 `SET` 语句中的表现。
 ///   - `ignore`: 忽略该字段。
@@ -215,13 +239,14 @@ pub fn where_attribute_macro(item: TokenStream) -> TokenStream {
 ///   - `condition`: 当设置 `r#where` 时生效
 ///   - `value`: 自定义字段的值，接受字符串类型。
 ///   - `index`: 自定义占位符序号（如果数据库支持），接受整型。
-//
+///   - `quote`: 是否使用数据库的引号包裹 `{name}`，覆盖结构上的 `quote_identifiers`。
+///
 /// 宏的优先级：`ignore` > `ignore_none` > `r#where` = `ignore_set` > `rename` = `value` = `condition` > `index`
 ///
 /// 示例：
 /// #[doc = "hidden"]
 /// #[cfg(test)]
-/// ```rust
+/// ```ignore
 /// #[derive(GenSet, Debug)]
 /// #[config(database = "postgres", index = 4)]
 /// pub struct SetStruct {
@@ -264,3 +289,82 @@ pub fn where_attribute_macro(item: TokenStream) -> TokenStream {
 pub fn set_attribute_macro(item: TokenStream) -> TokenStream {
     set::gen_set_attribute_impl(item)
 }
+
+/// `GenInsert` 派生宏
+///
+/// 用于生成完整的 SQL `INSERT` 语句。此宏依赖于 `InsertAttributeMacro` trait。
+/// 字段部分使用 `#[field(...)]` 的规则（与 `GenFields` 相同），值部分使用 `#[value(...)]` 的规则（与 `GenValues` 相同），
+/// 因此已经派生了 `GenFields` 和 `GenValues` 的结构体可以直接加上 `GenInsert`。
+///
+/// 宏参数：
+/// - `#[config(...)]`: 设置全局配置。
+///   - `table`: 表名，必须设置。
+///   - `database`: 指定数据库类型，影响占位符格式（支持 postgres, mysql, sqlite, mariadb, mssql）。
+///   - `index`: 设置占位符的起始索引。
+///   - `quote_identifiers`: 是否使用数据库的引号包裹字段名，字段上可以使用 `#[field(quote = ...)]` 覆盖。
+///
+/// 字段数量与值数量不一致时（例如只在其中一边设置了 `ignore`）会产生编译错误。
+///
+/// 示例：
+/// ```ignore
+/// use sql_tool_core::InsertAttributeMacro;
+///
+/// #[derive(GenInsert)]
+/// #[config(table = "users", database = "postgres")]
+/// pub struct InsertStruct {
+///     pub name: String,
+///     #[field(rename = "type")]
+///     pub ty: i32,
+///     #[value(value = "now()")]
+///     pub created_at: (),
+///     #[field(ignore)]
+///     #[value(ignore)]
+///     pub page_info: usize,
+/// }
+///
+/// // 输出：INSERT INTO users (name, type, created_at) VALUES ($1, $2, now())
+/// InsertStruct::generate_insert_sql();
+/// ```
+#[proc_macro_derive(GenInsert, attributes(field, value, config))]
+pub fn insert_attribute_macro(item: TokenStream) -> TokenStream {
+    insert::gen_insert_attribute_impl(item)
+}
+
+/// `GenDelete` 派生宏
+///
+/// 用于生成完整的 SQL `DELETE` 语句。此宏依赖于 `DeleteAttributeMacro` trait。
+/// `WHERE` 部分使用 `#[r#where(...)]` 的规则（与 `GenWhere` 相同）。
+/// 使用方法 `delete_data.generate_delete_sql()` 返回 `DELETE FROM table WHERE ...`，
+/// 使用方法 `delete_data.generate_delete_sql_with_args()` 会同时返回按占位符顺序排列的参数列表。
+///
+/// 当所有条件都被 `ignore_none` 过滤掉时会返回 `Err(SqlToolError::MissingWhere)`，
+/// 避免意外删除整张表，如果确实需要删除整张表，需要设置 `allow_full_delete = true`。
+///
+/// 宏参数：
+/// - `#[config(...)]`: 设置全局配置，除以下参数外与 `GenWhere` 相同。
+///   - `table`: 表名，必须设置。
+///   - `allow_full_delete`: 是否允许生成没有 `WHERE` 条件的 `DELETE` 语句，默认为 `false`。
+///
+/// 示例：
+/// ```ignore
+/// use sql_tool_core::DeleteAttributeMacro;
+///
+/// #[derive(GenDelete)]
+/// #[config(table = "advert", database = "postgres")]
+/// pub struct DeleteForm {
+///     #[r#where()]
+///     pub id: Option<i32>,
+///     #[r#where(condition = "<=")]
+///     pub end_time: Option<String>,
+/// }
+///
+/// let data = DeleteForm { id: None, end_time: Some("2024/12/12".to_string()) };
+/// data.generate_delete_sql(); // 输出：Ok("DELETE FROM advert WHERE end_time <= $1")
+///
+/// let data = DeleteForm { id: None, end_time: None };
+/// data.generate_delete_sql(); // 输出：Err(SqlToolError::MissingWhere)
+/// ```
+#[proc_macro_derive(GenDelete, attributes(r#where, config))]
+pub fn delete_attribute_macro(item: TokenStream) -> TokenStream {
+    delete::gen_delete_attribute_impl(item)
+}
@@ -1,59 +1,560 @@
-use syn::{Expr, Lit, MetaNameValue};
+use proc_macro2::TokenStream;
+use proc_macro_crate::{crate_name, FoundCrate};
+use quote::{quote, ToTokens};
+use sql_tool_core::Dialect;
+use std::fmt::Display;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{
+    bracketed, Attribute, Data, DeriveInput, Expr, Field, Fields, Ident, Lit, LitStr, Meta,
+    MetaNameValue, Token, Type,
+};
 
-/// 生成数据库特定的查询参数占位符模板。
+/// 用于在一次展开中收集多个 `syn::Error`。
 ///
-/// 此函数根据指定的数据库类型生成一个占位符模板字符串。返回的占位符模板
-/// 包含 `{index}`，这应当在实际使用中被替换为具体的参数索引值。
+/// 派生宏在解析属性时不会遇到第一个错误就停止，而是将所有错误收集起来，
+/// 最终合并成一个 `syn::Error`，这样所有的 `compile_error!` 都会指向各自出错的位置。
+#[derive(Default)]
+pub struct Errors {
+    inner: Option<syn::Error>,
+}
+
+impl Errors {
+    /// 添加一个错误
+    pub fn push(&mut self, error: syn::Error) {
+        match &mut self.inner {
+            Some(inner) => inner.combine(error),
+            None => self.inner = Some(error),
+        }
+    }
+
+    /// 在 `tokens` 的位置上添加一个错误
+    pub fn push_spanned<T: ToTokens, U: Display>(&mut self, tokens: T, message: U) {
+        self.push(syn::Error::new_spanned(tokens, message));
+    }
+
+    /// 如果 `result` 为错误，则收集该错误并返回 `None`
+    pub fn handle<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    /// 结束收集，存在错误时返回合并后的错误
+    pub fn finish(self) -> syn::Result<()> {
+        match self.inner {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 将派生宏的展开结果转换为 `TokenStream`，错误会被转换为 `compile_error!`
+pub fn into_token_stream(result: syn::Result<TokenStream>) -> proc_macro::TokenStream {
+    result.unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// 返回生成的代码中引用 `sql_tool_core` 的路径。
+///
+/// 调用方直接依赖 `sql_tool_core` 时为 `::sql_tool_core`，只依赖 `sql_tool_kit` 时为 `::sql_tool_kit`，
+/// 生成的代码只使用完整路径，调用方不需要额外导入任何类型。
+pub fn core_path() -> TokenStream {
+    let found = crate_name("sql_tool_core").or_else(|_| crate_name("sql_tool_kit"));
+    match found {
+        Ok(FoundCrate::Itself) => quote! { crate },
+        Ok(FoundCrate::Name(name)) => {
+            let name = Ident::new(&name, proc_macro2::Span::call_site());
+            quote! { ::#name }
+        }
+        Err(_) => quote! { ::sql_tool_core },
+    }
+}
+
+/// 获取结构体的具名字段，不是结构体或者不是具名字段时返回错误
+pub fn named_fields(input: &DeriveInput) -> syn::Result<Vec<&Field>> {
+    match &input.data {
+        Data::Struct(data_struct) => match &data_struct.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            Fields::Unit => Ok(Vec::new()),
+            Fields::Unnamed(fields) => {
+                Err(syn::Error::new_spanned(fields, "只支持具名字段的结构体"))
+            }
+        },
+        _ => Err(syn::Error::new_spanned(&input.ident, "只支持结构体")),
+    }
+}
+
+/// 返回具名字段的名称
+pub fn field_ident(field: &Field) -> &Ident {
+    field.ident.as_ref().expect("named_fields 只会返回具名字段")
+}
+
+/// 判断字段类型是否为 `Option<T>`
+pub fn is_option(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        return type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option");
+    }
+    false
+}
+
+/// 判断字段类型是否为 `Option<Option<T>>`，这类字段的 `Some(None)` 表示显式的 `NULL`
+pub fn is_nullable(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident != "Option" {
+        return false;
+    }
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return false;
+    };
+    arguments.args.iter().any(|argument| match argument {
+        syn::GenericArgument::Type(inner) => is_option(inner),
+        _ => false,
+    })
+}
+
+/// 生成 `Option<Option<T>>` 字段的代码：`None` 时跳过该字段，`Some(None)` 时执行 `push_null`，
+/// `Some(Some(value))` 时执行 `push_value`，其中 `value` 为内层值的引用
+pub fn nullable_tokens(
+    field: &Ident,
+    push_null: TokenStream,
+    push_value: TokenStream,
+) -> TokenStream {
+    quote! {
+        match &self.#field {
+            Some(Some(value)) => { #push_value }
+            Some(None) => { #push_null }
+            None => {}
+        }
+    }
+}
+
+/// 查找名称为 `name` 的属性
+pub fn find_attribute<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs.iter().find(|attr| attr.path().is_ident(name))
+}
+
+/// 查找结构体上 `#[config(...)]` 中名称为 `name` 的参数
+fn find_config_meta(input: &DeriveInput, name: &str) -> syn::Result<Option<Meta>> {
+    let Some(attr) = find_attribute(&input.attrs, "config") else {
+        return Ok(None);
+    };
+    Ok(parse_nested(attr)?
+        .into_iter()
+        .find(|meta| meta.path().is_ident(name)))
+}
+
+/// 读取结构体上 `#[config(table = "...")]` 设置的表名，未设置时返回 `None`
+pub fn config_table(input: &DeriveInput) -> syn::Result<Option<String>> {
+    let Some(meta) = find_config_meta(input, "table")? else {
+        return Ok(None);
+    };
+    let name_value = require_name_value(&meta)?;
+    let table = name_value_to_string(name_value)?;
+    if table.trim().is_empty() {
+        return Err(syn::Error::new_spanned(
+            &name_value.value,
+            "`table` 不能为空",
+        ));
+    }
+    Ok(Some(table))
+}
+
+/// 读取结构体上 `#[config(conflict = ["id", ...])]` 设置的冲突字段，未设置时返回 `None`
+pub fn config_conflict(input: &DeriveInput) -> syn::Result<Option<Vec<LitStr>>> {
+    let Some(meta) = find_config_meta(input, "conflict")? else {
+        return Ok(None);
+    };
+    let name_value = require_name_value(&meta)?;
+    let error = || {
+        syn::Error::new_spanned(
+            &name_value.value,
+            "`conflict` 的值必须是字符串数组，如 `conflict = [\"id\"]`",
+        )
+    };
+    let parser = |input: ParseStream| {
+        let content;
+        bracketed!(content in input);
+        Punctuated::<LitStr, Token![,]>::parse_terminated(&content)
+    };
+    let columns: Vec<LitStr> = parser
+        .parse2(name_value.value.to_token_stream())
+        .map_err(|_| error())?
+        .into_iter()
+        .collect();
+    if let Some(column) = columns
+        .iter()
+        .find(|column| column.value().trim().is_empty())
+    {
+        return Err(syn::Error::new_spanned(column, "冲突字段不能为空"));
+    }
+    if columns.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &name_value.value,
+            "`conflict` 至少需要一个字段",
+        ));
+    }
+    Ok(Some(columns))
+}
+
+/// 读取结构体上 `#[config(alias = "...")]` 设置的表别名，未设置时返回 `None`
+pub fn config_alias(input: &DeriveInput) -> syn::Result<Option<String>> {
+    let Some(meta) = find_config_meta(input, "alias")? else {
+        return Ok(None);
+    };
+    let name_value = require_name_value(&meta)?;
+    let alias = name_value_to_string(name_value)?;
+    if !is_plain_identifier(&alias) {
+        return Err(syn::Error::new_spanned(
+            &name_value.value,
+            "`alias` 只能包含字母、数字和下划线，且不能以数字开头",
+        ));
+    }
+    Ok(Some(alias))
+}
+
+/// 生成表示表别名的 `Option<&str>` 代码
+pub fn alias_tokens(alias: Option<&str>) -> TokenStream {
+    match alias {
+        Some(alias) => quote! { Some(#alias) },
+        None => quote! { None },
+    }
+}
+
+/// 读取结构体上 `#[config(name = true/false)]` 设置的布尔值，未设置时返回 `false`
+pub fn config_bool(input: &DeriveInput, name: &str) -> syn::Result<bool> {
+    match find_config_meta(input, name)? {
+        Some(meta) => name_value_to_bool(require_name_value(&meta)?),
+        None => Ok(false),
+    }
+}
+
+/// 读取结构体上 `#[config(database = "...")]` 设置的数据库，未设置时返回 `None`
+pub fn config_dialect(input: &DeriveInput) -> syn::Result<Option<Dialect>> {
+    match find_config_meta(input, "database")? {
+        Some(meta) => name_value_to_dialect(require_name_value(&meta)?).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 读取结构体上的 `#[config(quote_identifiers = true/false)]` 与 `#[config(database = "...")]`。
+///
+/// 返回默认使用的数据库以及是否默认使用引号包裹字段名。
+/// 需要引号包裹时必须知道数据库类型，`needs_dialect` 为 `true` 且没有设置 `database` 时返回错误。
+pub fn config_quote(input: &DeriveInput, needs_dialect: bool) -> syn::Result<(Dialect, bool)> {
+    let quote_identifiers = config_bool(input, "quote_identifiers")?;
+    match config_dialect(input)? {
+        Some(dialect) => Ok((dialect, quote_identifiers)),
+        None if needs_dialect || quote_identifiers => Err(syn::Error::new_spanned(
+            &input.ident,
+            "使用引号包裹字段名时必须设置 `#[config(database = \"/*数据库类型*/\")]`",
+        )),
+        None => Ok((Dialect::Postgres, quote_identifiers)),
+    }
+}
+
+/// 解析 `#[attr(a, b = "c", ...)]` 中的参数列表。
+///
+/// 除了普通的参数之外，还接受关键字 `in`（如 `#[r#where(in)]`），解析为名称为 `in` 的 `Meta::Path`。
+pub fn parse_nested(attr: &Attribute) -> syn::Result<Punctuated<Meta, Token![,]>> {
+    let parse_meta = |input: ParseStream| {
+        if input.peek(Token![in]) {
+            let keyword: Token![in] = input.parse()?;
+            return Ok(Meta::Path(Ident::new("in", keyword.span).into()));
+        }
+        input.parse::<Meta>()
+    };
+    attr.parse_args_with(|input: ParseStream| Punctuated::parse_terminated_with(input, parse_meta))
+}
+
+/// 返回 `meta` 的名称，用于错误提示
+pub fn meta_name(meta: &Meta) -> String {
+    meta.path()
+        .get_ident()
+        .map(|ident| ident.to_string())
+        .unwrap_or_else(|| meta.path().to_token_stream().to_string())
+}
+
+/// 生成未知参数的错误
+pub fn unknown_meta(meta: &Meta, attr_name: &str) -> syn::Error {
+    syn::Error::new_spanned(
+        meta,
+        format!("`#[{}(...)]` 不支持参数 `{}`", attr_name, meta_name(meta)),
+    )
+}
+
+/// 将 `#[config(database = "...")]` 的值解析为 `Dialect`。
 ///
 /// # 参数
-/// - `database`: 要生成占位符的数据库类型，如 "postgres"、"mysql"、"sqlite" 或 "mssql"。
+/// - `database`: 数据库类型，如 "postgres"、"mysql"、"sqlite" 或 "mssql"。
+///
+/// # Errors
+/// 如果提供了不支持的数据库类型，则返回指向该字符串的错误。
+pub fn parse_dialect(database: &LitStr) -> syn::Result<Dialect> {
+    Dialect::from_name(&database.value()).ok_or_else(|| {
+        syn::Error::new_spanned(
+            database,
+            format!(
+                "未支持的数据库类型 `{}`，目前支持：postgres, mysql, mariadb, sqlite, mssql",
+                database.value()
+            ),
+        )
+    })
+}
+
+/// 判断名称是否为普通的标识符（如 `user_id`），表达式或已经带有表名的名称（如 `u.id`）返回 `false`
+pub fn is_plain_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// 字段名是否使用数据库的引号包裹。
+///
+/// 字段上设置的 `quote` 优先；否则 `quote_identifiers` 只对普通的标识符生效，
+/// `rename` 为表达式或带有表名的名称（如 `lower(name)`、`u.id`）时保持原样。
+pub fn quote_column(quote: Option<bool>, quote_identifiers: bool, name: &str) -> bool {
+    quote.unwrap_or(quote_identifiers && is_plain_identifier(name))
+}
+
+/// 生成的 SQL 中的字段名
+pub struct ColumnName<'a> {
+    /// 字段名称或 `rename` 指定的名称
+    pub name: &'a str,
+    /// 是否使用数据库的引号包裹
+    pub quote: bool,
+    /// 是否在名称前加上表别名，只对普通的标识符生效
+    pub qualify: bool,
+}
+
+impl<'a> ColumnName<'a> {
+    pub fn new(name: &'a str, quote: bool, qualify: bool) -> Self {
+        ColumnName {
+            name,
+            quote,
+            qualify: qualify && is_plain_identifier(name),
+        }
+    }
+
+    /// 是否需要在运行时渲染
+    fn is_runtime(&self) -> bool {
+        self.quote || self.qualify
+    }
+
+    /// 生成渲染字段名的代码，返回 `String`。
+    ///
+    /// 生成的代码依赖作用域中的 `dialect: Dialect` 变量，需要加上表别名时还依赖 `alias: Option<&str>` 变量。
+    pub fn render(&self) -> TokenStream {
+        let name = self.name;
+        let render = if self.quote {
+            quote! { dialect.quote_identifier(#name) }
+        } else {
+            quote! { #name.to_string() }
+        };
+        if !self.qualify {
+            return render;
+        }
+        quote! {
+            match alias {
+                Some(alias) => format!("{}.{}", alias, #render),
+                None => #render,
+            }
+        }
+    }
+}
+
+/// 生成将 `template` 中的 `{index}` 替换为占位符的代码。
 ///
-/// # 返回值
-/// 返回含有 `{index}` 的数据库特定的占位符模板字符串。
+/// 生成的代码依赖作用域中的 `dialect: Dialect` 和 `index: usize` 变量，
+/// 设置了 `field_index` 时使用 `field_index` 作为占位符的序号。
+/// `name` 不为 `None` 时同时替换 `{name}`，需要引号或表别名时在运行时替换。
+pub fn render_template(
+    template: &str,
+    field_index: Option<usize>,
+    name: Option<&ColumnName>,
+) -> TokenStream {
+    let mut template = template.to_string();
+    let mut render = quote! { #template };
+    let mut replaced = false;
+    if let Some(name) = name.filter(|_| template.contains("{name}")) {
+        if name.is_runtime() {
+            let name = name.render();
+            render = quote! { #render.replace("{name}", &#name) };
+            replaced = true;
+        } else {
+            template = template.replace("{name}", name.name);
+            render = quote! { #template };
+        }
+    }
+    if template.contains("{index}") {
+        let index = match field_index {
+            Some(field_index) => quote! { #field_index },
+            None => quote! { index },
+        };
+        render = quote! { #render.replace("{index}", &dialect.placeholder(#index)) };
+        replaced = true;
+    }
+    if replaced {
+        render
+    } else {
+        quote! { #render.to_string() }
+    }
+}
+
+/// 生成将 `value` 写入 `args` 的代码，`count` 为模板中 `{index}` 出现的次数。
 ///
-/// # Panics
-/// 如果提供了不支持的数据库类型，则函数将 panic。
+/// 有序号的占位符（如 `$1`）重复出现时只需绑定一次，使用 `?` 的数据库则每个占位符都需要绑定。
+pub fn bind_args(value: TokenStream, count: usize) -> TokenStream {
+    let core = core_path();
+    match count {
+        0 => quote! {},
+        1 => quote! { args.push(#core::SqlArg::Ref(#value)); },
+        count => quote! {
+            args.push(#core::SqlArg::Ref(#value));
+            if !dialect.numbered_placeholders() {
+                for _ in 1..#count {
+                    args.push(#core::SqlArg::Ref(#value));
+                }
+            }
+        },
+    }
+}
+
+/// 生成构造 `SqlFragment` 的代码。
 ///
-/// # 示例
-/// ```
-/// let placeholder_template = generate_placeholder("postgres");
-/// let placeholder = placeholder_template.replace("{index}", "1");
-/// assert_eq!(placeholder, "$1");
-/// ```
-pub fn generate_placeholder(database: &str) -> String {
-    match database {
-        "postgres" => "${index}".to_string(),
-        "mysql" | "mariadb" => "?".to_string(),
-        "sqlite" => "?".to_string(),
-        "mssql" => "@p{index}".to_string(),
-        _ => panic!("未支持的数据库类型"),
+/// `render` 为返回 `String` 的代码，`field` 为来源的结构体字段，需要绑定参数时 `index` 为占位符序号的代码，
+/// `expression` 表示片段中包含 `rename`、`value` 等自定义的 SQL 表达式。
+pub fn fragment_tokens(
+    render: TokenStream,
+    field: &str,
+    index: Option<TokenStream>,
+    expression: bool,
+) -> TokenStream {
+    let core = core_path();
+    let index = index.map(|index| quote! { .with_index(#index) });
+    let expression = expression.then(|| quote! { .with_expression() });
+    quote! { #core::SqlFragment::new(#render, #field) #index #expression }
+}
+
+/// 解析 `database = "..."`，返回对应的 `Dialect`
+pub fn name_value_to_dialect(name_value: &MetaNameValue) -> syn::Result<Dialect> {
+    parse_dialect(&name_value_to_lit_str(name_value)?)
+}
+
+/// 生成在运行时表示 `dialect` 的代码，如 `Dialect::Postgres`
+pub fn dialect_tokens(dialect: Dialect) -> TokenStream {
+    let core = core_path();
+    match dialect {
+        Dialect::Postgres => quote! { #core::Dialect::Postgres },
+        Dialect::MySql => quote! { #core::Dialect::MySql },
+        Dialect::MariaDb => quote! { #core::Dialect::MariaDb },
+        Dialect::Sqlite => quote! { #core::Dialect::Sqlite },
+        Dialect::MsSql => quote! { #core::Dialect::MsSql },
     }
 }
 
-pub fn name_value_to_string(name_value: &MetaNameValue) -> Option<String> {
+fn name_value_to_lit_str(name_value: &MetaNameValue) -> syn::Result<LitStr> {
     if let Expr::Lit(value) = &name_value.value {
         if let Lit::Str(val) = &value.lit {
-            return Some(val.value());
+            return Ok(val.clone());
         }
     }
-    None
+    Err(syn::Error::new_spanned(
+        &name_value.value,
+        format!("`{}` 的值必须是字符串", name_value.path.to_token_stream()),
+    ))
+}
+
+pub fn name_value_to_string(name_value: &MetaNameValue) -> syn::Result<String> {
+    name_value_to_lit_str(name_value).map(|value| value.value())
 }
 
-pub fn name_value_to_bool(name_value: &MetaNameValue) -> Option<bool> {
+pub fn name_value_to_bool(name_value: &MetaNameValue) -> syn::Result<bool> {
     if let Expr::Lit(value) = &name_value.value {
         if let Lit::Bool(val) = &value.lit {
-            return Some(val.value());
+            return Ok(val.value());
         }
     }
-    None
+    Err(syn::Error::new_spanned(
+        &name_value.value,
+        format!(
+            "`{}` 的值必须是 `true` 或 `false`",
+            name_value.path.to_token_stream()
+        ),
+    ))
 }
 
-pub fn from_name_value<T>(name_value: &MetaNameValue) -> Option<T>
-where
-    T: From<Lit>,
-{
+pub fn name_value_to_usize(name_value: &MetaNameValue) -> syn::Result<usize> {
     if let Expr::Lit(value) = &name_value.value {
-        return Some(T::from(value.lit.clone()));
+        if let Lit::Int(val) = &value.lit {
+            if let Ok(val) = val.base10_parse::<usize>() {
+                return Ok(val);
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(
+        &name_value.value,
+        format!("`{}` 的值必须是非负整数", name_value.path.to_token_stream()),
+    ))
+}
+
+/// 检查 `meta` 是否为不带值的参数，如 `ignore`
+pub fn require_path(meta: &Meta) -> syn::Result<()> {
+    match meta {
+        Meta::Path(_) => Ok(()),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            format!(
+                "`{}` 不接受参数，应直接写为 `{}`",
+                meta_name(meta),
+                meta_name(meta)
+            ),
+        )),
+    }
+}
+
+/// 检查 `meta` 是否为 `name = value` 形式的参数
+pub fn require_name_value(meta: &Meta) -> syn::Result<&MetaNameValue> {
+    match meta {
+        Meta::NameValue(name_value) => Ok(name_value),
+        _ => Err(syn::Error::new_spanned(
+            meta,
+            format!("`{}` 应写为 `{} = ...`", meta_name(meta), meta_name(meta)),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_conflict_test() {
+        let input: DeriveInput = syn::parse_str(
+            r#"#[config(table = "advert", conflict = ["id", "tenant_id"])]
+            struct Advert { id: i32, tenant_id: i32 }"#,
+        )
+        .unwrap();
+        let columns: Vec<String> = config_conflict(&input)
+            .unwrap()
+            .unwrap()
+            .iter()
+            .map(LitStr::value)
+            .collect();
+        assert_eq!(vec!["id", "tenant_id"], columns);
     }
-    None
 }
@@ -2,9 +2,15 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Expr, Lit, Meta, Token};
+use syn::{parse_macro_input, DeriveInput};
 
-use syn::punctuated::Punctuated;
+use crate::fields::{render_names, FieldColumn};
+use crate::macro_utils::{
+    alias_tokens, config_alias, config_quote, config_table, core_path, dialect_tokens, field_ident,
+    find_attribute, into_token_stream, meta_name, name_value_to_bool, name_value_to_string,
+    named_fields, parse_nested, quote_column, require_name_value, require_path, unknown_meta,
+    Errors,
+};
 
 /// 生成针对特定结构体的 `SelectAttributeMacro` 实现。
 ///
@@ -21,60 +27,107 @@ use syn::punctuated::Punctuated;
 /// 此函数将为 `MyStruct` 生成相应的 `FieldsAttributeMacro` 实现。
 pub fn gen_select_attribute_impl(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
+    into_token_stream(expand(&input))
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
     let name = &input.ident;
+    let mut errors = Errors::default();
 
-    let fields = if let Data::Struct(data_struct) = &input.data {
-        let mut fields = Vec::new();
-        for field in &data_struct.fields {
-            let mut field_name = Some(field.ident.as_ref().unwrap().to_string());
-            let attrs = field
-                .attrs
-                .iter()
-                .find(|attr| attr.path().is_ident("select"));
+    let mut fields = Vec::new();
+    for field in named_fields(input)? {
+        let field_value = field_ident(field).to_string();
+        let mut field_name = Some(field_value.clone());
+        let mut quote = None;
+        let attrs = find_attribute(&field.attrs, "select");
 
-            if let Some(attr) = attrs {
-                let nested = attr
-                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-                    .map_err(|e| {
-                        println!("Error parsing 'select' attribute");
-                        e
-                    })
-                    .unwrap();
+        if let Some(attr) = attrs {
+            let Some(nested) = errors.handle(parse_nested(attr)) else {
+                continue;
+            };
 
-                for meta in nested {
-                    match meta {
-                        Meta::Path(_) if meta.path().is_ident("ignore") => {
+            for meta in nested {
+                match meta_name(&meta).as_str() {
+                    "ignore" => {
+                        if errors.handle(require_path(&meta)).is_some() {
                             field_name = None;
-                            break;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("rename") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Str(val) = &value.lit {
-                                    field_name = Some(val.value());
-                                }
+                    }
+                    "rename" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            if field_name.is_some() {
+                                field_name = Some(value);
                             }
                         }
-                        _ => {}
                     }
+                    "quote" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                        {
+                            quote = Some(value);
+                        }
+                    }
+                    _ => errors.push(unknown_meta(&meta, "select")),
                 }
             }
-            if let Some(value) = field_name {
-                fields.push(value);
-            }
         }
+        if let Some(value) = field_name {
+            fields.push((field_value, value, quote));
+        }
+    }
+    let table = match errors.handle(config_table(input)).flatten() {
+        Some(table) => quote! { Some(#table) },
+        None => quote! { None },
+    };
+    let needs_dialect = fields.iter().any(|(_, _, quote)| *quote == Some(true));
+    let config = errors.handle(config_quote(input, needs_dialect));
+    let alias = errors.handle(config_alias(input)).flatten();
+    errors.finish()?;
 
-        fields
-    } else {
-        Vec::new()
+    let Some((dialect, quote_identifiers)) = config else {
+        unreachable!("存在错误时已经提前返回");
     };
+    let fields: Vec<_> = fields
+        .into_iter()
+        .map(|(field, name, quote)| FieldColumn {
+            quote: quote_column(quote, quote_identifiers, &name),
+            field,
+            name,
+        })
+        .collect();
+    let dialect = dialect_tokens(dialect);
+    let fields = render_names(&fields, true);
+    let table_alias = alias_tokens(alias.as_deref());
 
-    let expanded = quote! {
-        impl SelectAttributeMacro for #name {
-            fn generate_select_clause() -> Vec<String> {
-                vec![#(#fields.to_string()),*]
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #core::SelectAttributeMacro for #name #ty_generics #where_clause {
+            fn generate_select_fragments() -> #core::Clause {
+                Self::generate_select_fragments_for(#dialect)
+            }
+            fn generate_select_fragments_for(dialect: #core::Dialect) -> #core::Clause {
+                let alias: Option<&str> = #table_alias;
+                let mut fields = #core::Clause::new();
+                #(fields.push(#fields);)*
+                fields
+            }
+            fn generate_select_fragments_with_alias(alias: &str) -> #core::Clause {
+                let alias = Some(alias);
+                let dialect = #dialect;
+                let mut fields = #core::Clause::new();
+                #(fields.push(#fields);)*
+                fields
+            }
+            fn table_alias() -> Option<&'static str> {
+                #table_alias
+            }
+            fn table_name() -> Option<&'static str> {
+                #table
             }
         }
-    };
-
-    TokenStream::from(expanded)
+    })
 }
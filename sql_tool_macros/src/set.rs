@@ -1,293 +1,479 @@
+use crate::bind::{BindArgs, BindField, BindValue};
 use crate::macro_utils::{
-    from_name_value, generate_placeholder, name_value_to_bool, name_value_to_string,
+    bind_args, config_table, core_path, dialect_tokens, field_ident, find_attribute,
+    fragment_tokens, into_token_stream, is_nullable, is_option, is_plain_identifier, meta_name,
+    name_value_to_bool, name_value_to_dialect, name_value_to_string, name_value_to_usize,
+    named_fields, nullable_tokens, parse_nested, quote_column, render_template, require_name_value,
+    require_path, unknown_meta, ColumnName, Errors,
 };
 use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
-use syn::punctuated::Punctuated;
+use sql_tool_core::Dialect;
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Data, DeriveInput, Lit, Meta, Token, Type};
+use syn::{parse_macro_input, DeriveInput, Meta};
 
 pub fn gen_set_attribute_impl(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
+    into_token_stream(expand(&input))
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
     let name = &input.ident;
+    let mut errors = Errors::default();
 
     let mut index = 1;
-    let mut placeholder = String::new();
+    let mut dialect = Dialect::Postgres;
+    let mut has_database = false;
     let mut ignore_none = true;
     let mut ignore_no_macro_set = true;
     let mut ignore_set_and_where = false;
+    let mut allow_full_update = false;
+    let mut quote_identifiers = false;
 
-    let attrs = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("config"))
-        .expect("必须在结构上设置 `#[config(database = \"/*数据库类型*/\")]` 宏");
+    let attrs = find_attribute(&input.attrs, "config").ok_or_else(|| {
+        syn::Error::new_spanned(
+            name,
+            "必须在结构上设置 `#[config(database = \"/*数据库类型*/\")]` 宏",
+        )
+    })?;
 
-    let nested = attrs
-        .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-        .map_err(|e| {
-            println!("分析字段属性时出错");
-            e
-        })
-        .unwrap();
-
-    for meta in nested {
-        match meta {
-            Meta::NameValue(name_value) if meta.path().is_ident("database") => {
-                if let Some(value) = name_value_to_string(&name_value) {
-                    placeholder = generate_placeholder(value.as_str());
-                    continue;
+    for meta in parse_nested(attrs)? {
+        match meta_name(&meta).as_str() {
+            "database" => {
+                has_database = true;
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_dialect))
+                {
+                    dialect = value;
                 }
-                panic!("database 值转换失败")
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("ignore_none") => {
-                if let Some(value) = name_value_to_bool(&name_value) {
+            "ignore_none" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
                     ignore_none = value;
                 }
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("index") => {
-                if let Some(Lit::Int(value)) = &from_name_value(&name_value) {
-                    index = value.base10_parse::<usize>().unwrap();
+            "index" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_usize))
+                {
+                    index = value;
                 }
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("ignore_no_macro_set") => {
-                if let Some(value) = name_value_to_bool(&name_value) {
+            "ignore_no_macro_set" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
                     ignore_no_macro_set = value;
                 }
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("ignore_set_and_where") => {
-                if let Some(value) = name_value_to_bool(&name_value) {
-                    ignore_set_and_where = value
+            "ignore_set_and_where" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
+                    ignore_set_and_where = value;
                 }
             }
+            "allow_full_update" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
+                    allow_full_update = value;
+                }
+            }
+            "quote_identifiers" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
+                    quote_identifiers = value;
+                }
+            }
+            // `#[config(...)]` 可能同时被其他派生宏使用，这里忽略其他参数
             _ => {}
         }
     }
 
-    if placeholder.is_empty() {
-        panic!("database 值必须设置");
+    if !has_database {
+        errors.push_spanned(attrs, "`database` 值必须设置");
     }
 
-    let (set_values, where_values) = if let Data::Struct(data_struct) = &input.data {
-        let mut sets = Vec::new();
-        let mut wheres = Vec::new();
+    let mut set_values = Vec::new();
+    let mut where_values = Vec::new();
 
-        for field in &data_struct.fields {
-            let mut set_value = Some("{name} = {index}".to_string());
-            let mut where_value = None;
-            let field_name = field.ident.as_ref().unwrap().to_string();
-            let field_value = field.ident.clone().unwrap();
-            let mut ignore_none = ignore_none;
-            let mut rename = String::new();
-            let mut condition = "=".to_string();
-            let mut value_placeholder = String::new();
-            let mut field_index = -1;
+    for field in named_fields(input)? {
+        let mut set_value = Some("{name} = {index}".to_string());
+        let mut where_value = None;
+        let mut field_name = field_ident(field).to_string();
+        let field_value = field_ident(field);
+        let mut ignore = false;
+        let mut quote = None;
+        let mut ignore_none = ignore_none;
+        let mut rename = String::new();
+        let mut condition = "=".to_string();
+        let mut value_placeholder = String::new();
+        let mut field_index = None;
+        let mut custom_where = false;
 
-            let attrs = field.attrs.iter().find(|attr| attr.path().is_ident("set"));
+        let attrs = find_attribute(&field.attrs, "set");
 
-            if let Some(attr) = attrs {
-                let nested = attr
-                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-                    .map_err(|e| {
-                        println!("分析 'value' 属性时出错");
-                        e
-                    })
-                    .unwrap();
+        if let Some(attr) = attrs {
+            let Some(nested) = errors.handle(parse_nested(attr)) else {
+                continue;
+            };
 
-                for meta in nested {
-                    match meta {
-                        Meta::Path(_) if meta.path().is_ident("ignore") => {
+            for meta in nested {
+                match meta_name(&meta).as_str() {
+                    "ignore" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            ignore = true;
+                        }
+                    }
+                    "ignore_set" => {
+                        if errors.handle(require_path(&meta)).is_some() {
                             set_value = None;
-                            where_value = None;
-                            break;
                         }
-                        Meta::Path(_) if meta.path().is_ident("ignore_set") => {
+                    }
+                    "r#where" => match &meta {
+                        Meta::Path(_) => {
+                            where_value = Some("{name} {condition} {index}".to_string());
                             set_value = None;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("r#where") => {
-                            if let Some(value) = name_value_to_string(&name_value) {
+                        _ => {
+                            if let Some(value) = errors
+                                .handle(require_name_value(&meta).and_then(name_value_to_string))
+                            {
                                 where_value = Some(value);
+                                custom_where = true;
                                 if ignore_set_and_where {
                                     set_value = None;
                                 }
                             }
                         }
-                        Meta::Path(_) if meta.path().is_ident("r#where") => {
-                            where_value = Some("{name} {condition} {index}".to_string());
-                            set_value = None;
+                    },
+                    "ignore_none" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                        {
+                            ignore_none = value;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("ignore_none") => {
-                            if let Some(value) = name_value_to_bool(&name_value) {
-                                ignore_none = value;
-                            }
+                    }
+                    "rename" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            rename = value;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("rename") => {
-                            if let Some(value) = name_value_to_string(&name_value) {
-                                rename = value;
-                            }
+                    }
+                    "condition" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            condition = value;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("condition") => {
-                            if let Some(value) = name_value_to_string(&name_value) {
-                                condition = value;
-                            }
+                    }
+                    "value" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            value_placeholder = value;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("value") => {
-                            if let Some(value) = name_value_to_string(&name_value) {
-                                value_placeholder = value;
-                            }
+                    }
+                    "index" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_usize))
+                        {
+                            field_index = Some(value);
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("index") => {
-                            if let Some(Lit::Int(value)) = &from_name_value(&name_value) {
-                                field_index = value.base10_parse::<i32>().unwrap();
-                            }
+                    }
+                    "quote" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                        {
+                            quote = Some(value);
                         }
-                        _ => {}
                     }
+                    _ => errors.push(unknown_meta(&meta, "set")),
                 }
+            }
+            if ignore {
+                continue;
+            }
+            if !rename.is_empty() {
+                field_name = rename;
+            }
 
-                if let Some(mut field) = set_value {
-                    let name_to_use = if rename.is_empty() {
-                        &field_name
-                    } else {
-                        &rename
-                    };
-                    field = field.replace("{name}", name_to_use);
+            if let Some(mut field) = set_value {
+                if !value_placeholder.is_empty() {
+                    field = field.replace("{index}", &value_placeholder);
+                }
 
-                    if !value_placeholder.is_empty() {
-                        field = field.replace("{index}", &value_placeholder);
-                    }
+                set_value = Some(field);
+            }
+            if let Some(mut field) = where_value {
+                field = field.replace("{condition}", &condition);
 
-                    set_value = Some(field);
+                if !value_placeholder.is_empty() {
+                    field = field.replace("{index}", &value_placeholder);
                 }
-                if let Some(mut field) = where_value {
-                    let name_to_use = if rename.is_empty() {
-                        &field_name
-                    } else {
-                        &rename
-                    };
-                    field = field.replace("{name}", name_to_use);
-
-                    field = field.replace("{condition}", &condition);
 
-                    if !value_placeholder.is_empty() {
-                        field = field.replace("{index}", &value_placeholder);
-                    }
+                where_value = Some(field);
+            }
+        } else if ignore_no_macro_set {
+            set_value = None;
+        }
 
-                    where_value = Some(field);
-                }
+        // 占位符总是通过运行时的 `index` 渲染，设置了 `index` 的字段在渲染之前由 `renumber_tokens` 设置
+        let quote = quote_column(quote, quote_identifiers, &field_name);
+        let column = ColumnName::new(&field_name, quote, false);
+        let placeholder = quote! { index };
+        let field_str = field_ident(field).to_string();
+        let expression = !value_placeholder.is_empty() || !is_plain_identifier(&field_name);
+        // `Option<Option<T>>` 字段的 `None` 总是跳过，`Some(None)` 时不会占用占位符
+        let nullable = is_nullable(&field.ty);
+        let bind_value = if nullable {
+            quote! { value }
+        } else {
+            quote! { &self.#field_value }
+        };
+        if let Some(value) = set_value {
+            let add_index: usize = if field_index.is_none() && value.contains("{index}") {
+                1
             } else {
-                if let (false, Some(mut value)) = (ignore_no_macro_set, set_value.clone()) {
-                    value = value.replace("{name}", &field_name);
-                    set_value = Some(value);
+                0
+            };
+            let render = render_template(&value, None, Some(&column));
+            let count = value.matches("{index}").count();
+            let fragment = fragment_tokens(
+                render,
+                &field_str,
+                (count > 0).then(|| placeholder.clone()),
+                expression,
+            );
+            let args = bind_args(bind_value.clone(), count);
+            let push_data = quote! {
+                set_data.push(#fragment);
+                index += #add_index;
+                #args
+            };
+            let optional = ignore_none && is_option(&field.ty) && !nullable;
+            let bind = (count > 0).then(|| BindField {
+                ident: field_value.clone(),
+                optional,
+                index: field_index,
+                add_index,
+                count,
+                value: if nullable {
+                    BindValue::Nullable
                 } else {
-                    set_value = None;
+                    BindValue::Field
+                },
+            });
+            let get_data = if nullable {
+                let render = render_template("{name} = NULL", None, Some(&column));
+                let fragment = fragment_tokens(render, &field_str, None, expression);
+                nullable_tokens(field_value, quote! { set_data.push(#fragment); }, push_data)
+            } else if optional {
+                quote! {
+                    if self.#field_value.is_some() {
+                        #push_data
+                    }
                 }
-            }
+            } else {
+                quote_spanned! {field.span() => { #push_data }}
+            };
+            set_values.push((get_data, bind));
+        }
 
-            if let Some(value) = set_value {
-                let value = value.replace("{index}", &placeholder.replace("{index}", "{index}"));
-                let add_index: usize = if field_index == -1 && value.contains("{index}") {
-                    1
+        if let Some(value) = where_value {
+            let add_index: usize = if field_index.is_none() && value.contains("{index}") {
+                1
+            } else {
+                0
+            };
+            let render = render_template(&value, None, Some(&column));
+            let count = value.matches("{index}").count();
+            let fragment = fragment_tokens(
+                render,
+                &field_str,
+                (count > 0).then(|| placeholder.clone()),
+                expression || custom_where,
+            );
+            let args = bind_args(bind_value.clone(), count);
+            let push_data = quote! {
+                where_data.push(#fragment);
+                index += #add_index;
+                #args
+            };
+            let optional = ignore_none && is_option(&field.ty) && !nullable;
+            let bind = (count > 0).then(|| BindField {
+                ident: field_value.clone(),
+                optional,
+                index: field_index,
+                add_index,
+                count,
+                value: if nullable {
+                    BindValue::Nullable
                 } else {
-                    0
-                };
-                let get_data = if let Type::Path(type_path) = &field.ty {
-                    if ignore_none
-                        && type_path
-                            .path
-                            .segments
-                            .first()
-                            .map_or(false, |segment| segment.ident == "Option")
-                    {
-                        quote! {
-                            if self.#field_value.is_some() {
-                                (Some(#value), #add_index)
-                            } else {
-                                (None, #add_index)
-                            }
-                        }
-                    } else {
-                        quote_spanned! {field.span() => (Some(#value), #add_index)}
+                    BindValue::Field
+                },
+            });
+            let get_data = if nullable {
+                let render = render_template("{name} IS NULL", None, Some(&column));
+                let fragment = fragment_tokens(render, &field_str, None, expression);
+                nullable_tokens(
+                    field_value,
+                    quote! { where_data.push(#fragment); },
+                    push_data,
+                )
+            } else if optional {
+                quote! {
+                    if self.#field_value.is_some() {
+                        #push_data
                     }
-                } else {
-                    quote_spanned! {field.span() => (Some(#value), #add_index)}
-                };
-                sets.push(get_data);
-            }
+                }
+            } else {
+                quote_spanned! {field.span() => { #push_data }}
+            };
+            where_values.push((get_data, bind));
+        }
+    }
+    let table = errors.handle(config_table(input)).flatten();
+    // `SET` 部分生成在 `WHERE` 部分之前
+    let binds: Vec<BindField> = set_values
+        .iter()
+        .chain(&where_values)
+        .filter_map(|(_, bind)| bind.clone())
+        .collect();
+    let set_count = set_values.iter().filter(|(_, bind)| bind.is_some()).count();
+    // 与 `GenWhere` 相同，存在设置了 `index` 的字段时在运行时重新编号
+    let renumber = binds.iter().any(|bind| bind.index.is_some());
+    let [set_values, where_values] = [set_values, where_values].map(|values| {
+        values
+            .into_iter()
+            .map(|(block, bind)| match bind {
+                Some(bind) if renumber => bind.renumber_tokens(block),
+                _ => block,
+            })
+            .collect::<Vec<_>>()
+    });
+    let bind_args = errors.handle(BindArgs::renumbered(dialect, index, binds));
+    errors.finish()?;
 
-            if let Some(value) = where_value {
-                let value = value.replace("{index}", &placeholder.replace("{index}", "{index}"));
-                let add_index: usize = if field_index == -1 && value.contains("{index}") {
-                    1
-                } else {
-                    0
-                };
-                let get_data = if let Type::Path(type_path) = &field.ty {
-                    if ignore_none
-                        && type_path
-                            .path
-                            .segments
-                            .first()
-                            .map_or(false, |segment| segment.ident == "Option")
-                    {
-                        quote!(
-                            if set.#field_value.is_some() {
-                                (Some(#value), #add_index)
-                            } else {
-                                (None, #add_index)
-                            }
-                        )
-                    } else {
-                        quote_spanned! {field.span() => (Some(#value), #add_index)}
+    let Some(bind_args) = bind_args else {
+        unreachable!("存在错误时已经提前返回");
+    };
+    let set_slots = bind_args.slots_tokens_for(set_count);
+    let slots = bind_args.slots_tokens();
+    let next_index = bind_args.next_index_tokens();
+    let placeholder_map = bind_args.map_tokens();
+    let sqlx_impl = bind_args.sqlx_impl(input, "SqlxSetArgs", "bind_set_args");
+    let rusqlite_impl = bind_args.rusqlite_impl(input, "RusqliteSetParams");
+    let postgres_impl = bind_args.postgres_impl(input, "PgSetParams");
+    let dialect = dialect_tokens(dialect);
+    let update_sql = match table {
+        Some(table) => {
+            let prefix = format!("UPDATE {} SET ", table);
+            // 没有显式允许时，拒绝生成没有 WHERE 条件的 UPDATE 语句
+            let check_where = if allow_full_update {
+                quote! {}
+            } else {
+                quote! {
+                    if where_data.is_empty() {
+                        return Err(#core::SqlToolError::MissingWhere);
                     }
-                } else {
-                    quote_spanned! {field.span() => (Some(#value), #add_index)}
-                };
-                wheres.push(get_data);
+                }
+            };
+            quote! {
+                let (set_data, where_data) = self.generate_set_and_where_fragments_with_counter(
+                    &mut #core::ParamCounter::with_start(dialect, #index),
+                );
+                if set_data.is_empty() {
+                    return Err(#core::SqlToolError::EmptySet);
+                }
+                #check_where
+                let mut sql = format!("{}{}", #prefix, set_data.join(", "));
+                if !where_data.is_empty() {
+                    sql.push_str(" WHERE ");
+                    sql.push_str(&where_data.join(" AND "));
+                }
+                Ok(sql)
             }
         }
-        (sets, wheres)
-    } else {
-        (Vec::new(), Vec::new())
+        None => quote! {
+            Err(#core::SqlToolError::MissingTable)
+        },
     };
 
-    let expanded = quote! {
-        impl SetAttributeMacro  for #name {
-            fn generate_set_clause(&self) -> Vec<String> {
-                let mut fields = Vec::new();
-                let mut index = #index;
-                #(
-                    // 使用 values 中的每个 TokenStream
-                    if let (Some(value), add_index) = #set_values {
-                        let value = value.replace("{index}", &index.to_string());
-                        index = index + add_index;
-                        fields.push(value);
-                    }
-                )*
-                fields
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #core::SetAttributeMacro for #name #ty_generics #where_clause {
+            fn generate_set_clause_for(&self, dialect: #core::Dialect) -> Vec<String> {
+                self.generate_set_clause_with_counter(&mut #core::ParamCounter::with_start(dialect, #index))
             }
-            fn generate_set_and_where_clause(&self) -> (Vec<String>, Vec<String>) {
-                let mut set_data = Vec::new();
-                let mut where_data = Vec::new();
-                let mut index = #index;
-                #(
-                    if let (Some(value), add_index) = #set_values {
-                        let value = value.replace("{index}", &index.to_string());
-                        index = index + add_index;
-                        set_data.push(value);
-                    }
-                )*
-                #(
-                    if let (Some(value), add_index) = #where_values {
-                        let value = value.replace("{index}", &index.to_string());
-                        index = index + add_index;
-                        where_data.push(value);
-                    }
-                )*
+            fn generate_set_fragments(&self) -> #core::Clause {
+                self.generate_set_fragments_with_counter(&mut #core::ParamCounter::with_start(#dialect, #index))
+            }
+            fn generate_set_fragments_with_counter(&self, counter: &mut #core::ParamCounter) -> #core::Clause {
+                let dialect = counter.dialect();
+                let mut set_data = #core::Clause::new();
+                let mut args: Vec<#core::SqlArg<'_>> = Vec::new();
+                let mut index = counter.next_index();
+                #set_slots
+                #(#set_values)*
+                #next_index
+                counter.advance(index, args.len());
+                set_data
+            }
+            fn generate_set_and_where_clause_for(
+                &self,
+                dialect: #core::Dialect,
+            ) -> (Vec<String>, Vec<String>) {
+                self.generate_set_and_where_clause_with_counter(&mut #core::ParamCounter::with_start(
+                    dialect, #index,
+                ))
+            }
+            fn generate_set_and_where_fragments(&self) -> (#core::Clause, #core::Clause) {
+                self.generate_set_and_where_fragments_with_counter(&mut #core::ParamCounter::with_start(
+                    #dialect, #index,
+                ))
+            }
+            fn generate_set_and_where_fragments_with_counter(
+                &self,
+                counter: &mut #core::ParamCounter,
+            ) -> (#core::Clause, #core::Clause) {
+                let dialect = counter.dialect();
+                let mut set_data = #core::Clause::new();
+                let mut where_data = #core::Clause::new();
+                let mut args: Vec<#core::SqlArg<'_>> = Vec::new();
+                let mut index = counter.next_index();
+                #slots
+                #(#set_values)*
+                #(#where_values)*
+                #next_index
+                counter.advance(index, args.len());
                 (set_data, where_data)
             }
+            fn placeholder_map(&self) -> &'static [(usize, &'static str)] {
+                #placeholder_map
+            }
+            fn next_param_index(&self) -> usize {
+                let mut counter = #core::ParamCounter::with_start(#dialect, #index);
+                self.generate_set_and_where_fragments_with_counter(&mut counter);
+                counter.next_index()
+            }
+            fn generate_update_sql(&self) -> Result<String, #core::SqlToolError> {
+                self.generate_update_sql_for(#dialect)
+            }
+            fn generate_update_sql_for(&self, dialect: #core::Dialect) -> Result<String, #core::SqlToolError> {
+                #update_sql
+            }
         }
-    };
 
-    TokenStream::from(expanded)
+        #sqlx_impl
+        #rusqlite_impl
+        #postgres_impl
+    })
 }
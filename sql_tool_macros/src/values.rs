@@ -2,10 +2,15 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Expr, Lit, Meta, Token};
+use sql_tool_core::Dialect;
+use syn::{parse_macro_input, DeriveInput};
 
-use crate::macro_utils::{from_name_value, name_value_to_string};
-use syn::punctuated::Punctuated;
+use crate::bind::{BindArgs, BindField, BindValue};
+use crate::macro_utils::{
+    core_path, dialect_tokens, field_ident, find_attribute, fragment_tokens, into_token_stream,
+    meta_name, name_value_to_dialect, name_value_to_string, name_value_to_usize, named_fields,
+    parse_nested, render_template, require_name_value, require_path, unknown_meta, Errors,
+};
 
 /// 生成针对特定结构体的 `ValuesAttributeMacro` 实现。
 ///
@@ -64,129 +69,344 @@ use syn::punctuated::Punctuated;
 /// 此函数将为 `MyStruct` 生成相应的 `FieldsAttributeMacro` 实现。
 pub fn gen_values_attribute_impl(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    let name = &input.ident;
+    into_token_stream(expand(&input))
+}
 
-    let mut index = 1;
-    let mut placeholder = "";
+/// `#[value(...)]` 解析后的结果，供 `GenValues` 和 `GenInsert` 共用
+pub struct ValuesClause {
+    /// `#[config(database = "...")]` 设置的数据库
+    pub dialect: Dialect,
+    /// 占位符的起始索引
+    pub index: usize,
+    /// 每个字段的值
+    pub values: Vec<FieldValue>,
+    /// 设置了 `index` 的字段中最大的占位符序号加一，没有时为 `0`
+    pub max_index: usize,
+    /// 生成了占位符的字段，用于生成绑定参数的代码
+    pub binds: BindArgs,
+}
 
-    let attrs = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("config"))
-        .expect("必须在结构上设置 `#[config(database = \"/*数据库类型*/\")]` 宏");
-
-    let nested = attrs
-        .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-        .map_err(|e| {
-            println!("分析字段属性时出错");
-            e
-        })
-        .unwrap();
-
-    for meta in nested {
-        match meta {
-            Meta::NameValue(name_value) if meta.path().is_ident("database") => {
-                if let Expr::Lit(value) = &name_value.value {
-                    if let Lit::Str(value) = &value.lit {
-                        placeholder = match value.value().as_str() {
-                            "postgres" => "${index}",   // PostgreSQL 使用 $1, $2, ...
-                            "mysql" | "mariadb" => "?", // MySQL 和 MariaDB 使用 ?
-                            "sqlite" => "?",            // SQLite 也是使用 ?
-                            "mssql" => "@p{index}",     // Microsoft SQL Server 使用 @p1, @p2, ...
-                            _ => panic!("未支持的数据库类型"),
-                        };
-                        continue;
+/// 结构体字段在 `VALUES` 中对应的值
+pub struct FieldValue {
+    /// 结构体字段
+    pub ident: syn::Ident,
+    /// 值的模板，模板中的 `{index}` 会被替换为占位符
+    pub template: String,
+    /// `#[value(index = ...)]` 设置的序号
+    pub index: Option<usize>,
+    /// 模板是否来自 `#[value(value = "...")]`
+    pub expression: bool,
+    /// `#[value(conflict_update)]`，upsert 发生冲突时是否更新该字段
+    pub conflict_update: bool,
+}
+
+impl ValuesClause {
+    /// 从 `#[config(index = ...)]` 开始时，下一个可用的占位符序号
+    pub fn next_index(&self) -> usize {
+        let count = self
+            .values
+            .iter()
+            .filter(|value| value.index.is_none() && value.template.contains("{index}"))
+            .count();
+        (self.index + count).max(self.max_index)
+    }
+
+    /// 生成渲染所有值的代码。
+    ///
+    /// 生成的代码依赖作用域中的 `dialect`、`index`、`values: Clause` 和 `placeholders: usize` 变量，
+    /// 每个值会写入 `values`，并更新 `index` 以及占位符出现的次数 `placeholders`。
+    pub fn render_tokens(&self) -> Vec<proc_macro2::TokenStream> {
+        self.values
+            .iter()
+            .map(|value| {
+                let render = render_template(&value.template, value.index, None);
+                let count = value.template.matches("{index}").count();
+                let add_index = usize::from(value.index.is_none() && count > 0);
+                let placeholder = match value.index {
+                    Some(field_index) => quote! { #field_index },
+                    None => quote! { index },
+                };
+                let fragment = fragment_tokens(
+                    render,
+                    &value.ident.to_string(),
+                    (count > 0).then_some(placeholder),
+                    value.expression,
+                );
+                quote! {
+                    values.push(#fragment);
+                    index += #add_index;
+                    placeholders += #count;
+                }
+            })
+            .collect()
+    }
+
+    /// 批量插入时每行占用的占位符序号个数
+    pub fn row_stride(&self) -> usize {
+        self.next_index() - self.index
+    }
+
+    /// 使用 `?` 的数据库中每行需要绑定的参数个数
+    pub fn row_placeholders(&self) -> usize {
+        self.values
+            .iter()
+            .map(|value| value.template.matches("{index}").count())
+            .sum()
+    }
+
+    /// 生成渲染批量插入中一行值的代码。
+    ///
+    /// 与 `render_tokens` 相同，但设置了 `index` 且不小于起始索引的字段会加上作用域中的 `offset` 变量，
+    /// 生成的代码依赖作用域中的 `dialect`、`index`、`offset` 和 `values: Clause` 变量。
+    pub fn row_render_tokens(&self) -> Vec<proc_macro2::TokenStream> {
+        self.values
+            .iter()
+            .map(|value| {
+                let count = value.template.matches("{index}").count();
+                let field = value.ident.to_string();
+                match value.index {
+                    Some(field_index) if field_index >= self.index && count > 0 => {
+                        let render = render_template(&value.template, None, None);
+                        let fragment = fragment_tokens(
+                            render,
+                            &field,
+                            Some(quote! { index }),
+                            value.expression,
+                        );
+                        quote! {{
+                            let index = #field_index + offset;
+                            values.push(#fragment);
+                        }}
+                    }
+                    Some(field_index) => {
+                        let render = render_template(&value.template, Some(field_index), None);
+                        let fragment = fragment_tokens(
+                            render,
+                            &field,
+                            (count > 0).then(|| quote! { #field_index }),
+                            value.expression,
+                        );
+                        quote! { values.push(#fragment); }
                     }
+                    None => {
+                        let render = render_template(&value.template, None, None);
+                        let add_index = usize::from(count > 0);
+                        let fragment = fragment_tokens(
+                            render,
+                            &field,
+                            (count > 0).then(|| quote! { index }),
+                            value.expression,
+                        );
+                        quote! {
+                            values.push(#fragment);
+                            index += #add_index;
+                        }
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// 解析结构体上的 `#[config(...)]` 以及字段上的 `#[value(...)]`
+pub fn values_clause(input: &DeriveInput) -> syn::Result<ValuesClause> {
+    let mut errors = Errors::default();
+
+    let mut index = 1;
+    let mut dialect = Dialect::Postgres;
+    let mut max_index = 0;
+    let mut has_database = false;
+
+    let attrs = find_attribute(&input.attrs, "config").ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "必须在结构上设置 `#[config(database = \"/*数据库类型*/\")]` 宏",
+        )
+    })?;
+
+    for meta in parse_nested(attrs)? {
+        match meta_name(&meta).as_str() {
+            "database" => {
+                has_database = true;
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_dialect))
+                {
+                    dialect = value;
                 }
-                panic!("database 值转换失败")
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("index") => {
-                if let Some(Lit::Int(value)) = &from_name_value(&name_value) {
-                    index = value.base10_parse::<usize>().unwrap();
+            "index" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_usize))
+                {
+                    index = value;
                 }
             }
+            // `#[config(...)]` 可能同时被其他派生宏使用，这里忽略其他参数
             _ => {}
         }
     }
 
-    if placeholder.is_empty() {
-        panic!("database 值必须设置");
+    if !has_database {
+        errors.push_spanned(attrs, "`database` 值必须设置");
     }
 
-    let values = if let Data::Struct(data_struct) = &input.data {
-        let mut fields = Vec::new();
-        for field in &data_struct.fields {
-            let mut field_name = Some(placeholder.replace("{index}", &index.to_string()));
-            let mut value_placeholder = None;
-            let mut add_index = 1;
-            let attrs = field
-                .attrs
-                .iter()
-                .find(|attr| attr.path().is_ident("value"));
-
-            if let Some(attr) = attrs {
-                let nested = attr
-                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-                    .map_err(|e| {
-                        println!("分析 'value' 属性时出错");
-                        e
-                    })
-                    .unwrap();
-
-                for meta in nested {
-                    match meta {
-                        Meta::Path(_) if meta.path().is_ident("ignore") => {
-                            field_name = None;
-                            break;
-                        }
-                        Meta::NameValue(name_value) if meta.path().is_ident("index") => {
-                            if let Some(Lit::Int(value)) = &from_name_value(&name_value) {
-                                field_name =
-                                    Some(placeholder.replace("{index}", &value.to_string()));
-                                add_index = 0;
-                            }
+    let mut values = Vec::new();
+    for field in named_fields(input)? {
+        let mut ignore = false;
+        let mut field_index = None;
+        let mut value_placeholder = None;
+        let mut conflict_update = false;
+
+        if let Some(attr) = find_attribute(&field.attrs, "value") {
+            let Some(nested) = errors.handle(parse_nested(attr)) else {
+                continue;
+            };
+
+            for meta in nested {
+                match meta_name(&meta).as_str() {
+                    "ignore" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            ignore = true;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("value") => {
-                            if let Some(value) = name_value_to_string(&name_value) {
-                                if value.contains("{index}") {
-                                    value_placeholder = Some(value.replace(
-                                        "{index}",
-                                        &placeholder.replace("{index}", &index.to_string()),
-                                    ));
-                                } else {
-                                    value_placeholder = Some(value);
-                                    add_index = 0;
-                                }
-                            }
+                    }
+                    "index" => {
+                        field_index =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_usize));
+                    }
+                    "value" => {
+                        value_placeholder =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string));
+                    }
+                    "conflict_update" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            conflict_update = true;
                         }
-                        _ => {}
                     }
+                    _ => errors.push(unknown_meta(&meta, "value")),
                 }
             }
-            if let Some(value) = value_placeholder {
-                fields.push(value);
-                index += add_index;
-            } else if let Some(value) = field_name {
-                fields.push(value);
-                index += add_index;
-            }
         }
+        if ignore {
+            continue;
+        }
+
+        // 设置了 `index` 或者 `value` 中不包含 `{index}` 时，全局的 index 不会加一
+        let expression = value_placeholder.is_some();
+        let value = value_placeholder.unwrap_or_else(|| "{index}".to_string());
+        if let (Some(field_index), true) = (field_index, value.contains("{index}")) {
+            max_index = max_index.max(field_index + 1);
+        }
+        values.push(FieldValue {
+            ident: field_ident(field).clone(),
+            template: value,
+            index: field_index,
+            expression,
+            conflict_update,
+        });
+    }
+    let binds = errors.handle(BindArgs::new(dialect, index, bind_fields(&values)));
+    errors.finish()?;
 
-        fields
-    } else {
-        Vec::new()
+    let Some(binds) = binds else {
+        unreachable!("存在错误时已经提前返回");
     };
+    Ok(ValuesClause {
+        dialect,
+        index,
+        values,
+        max_index,
+        binds,
+    })
+}
+
+/// 生成了占位符的字段，用于生成绑定参数的代码
+fn bind_fields(values: &[FieldValue]) -> Vec<BindField> {
+    values
+        .iter()
+        .filter_map(|value| {
+            let count = value.template.matches("{index}").count();
+            (count > 0).then(|| BindField {
+                ident: value.ident.clone(),
+                optional: false,
+                index: value.index,
+                add_index: usize::from(value.index.is_none()),
+                count,
+                value: BindValue::Field,
+            })
+        })
+        .collect()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
+    let name = &input.ident;
+    let values_clause = values_clause(input)?;
+    let next_index = values_clause.next_index();
+    let index = values_clause.index;
+    let max_index = values_clause.max_index;
+    let bind_args = &values_clause.binds;
+    let placeholder_map = bind_args.map_tokens();
+    let sqlx_impl = bind_args.sqlx_impl(input, "SqlxValuesArgs", "bind_values_args");
+    let rusqlite_impl = bind_args.rusqlite_impl(input, "RusqliteValuesParams");
+    let postgres_impl = bind_args.postgres_impl(input, "PgValuesParams");
+    let dialect = dialect_tokens(values_clause.dialect);
+    let values = values_clause.render_tokens();
+    let row_values = values_clause.row_render_tokens();
+    let stride = values_clause.row_stride();
+    let row_placeholders = values_clause.row_placeholders();
+    let before = index.saturating_sub(1);
 
-    let expanded = quote! {
-        impl ValuesAttributeMacro for #name {
-            fn generate_values_clause() -> Vec<String> {
-                vec![#(#values.to_string()),*]
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #core::ValuesAttributeMacro for #name #ty_generics #where_clause {
+            fn generate_values_clause_for(dialect: #core::Dialect) -> Vec<String> {
+                Self::generate_values_clause_with_counter(&mut #core::ParamCounter::with_start(dialect, #index))
+            }
+            fn generate_values_fragments() -> #core::Clause {
+                Self::generate_values_fragments_with_counter(&mut #core::ParamCounter::with_start(#dialect, #index))
+            }
+            fn generate_values_fragments_with_counter(counter: &mut #core::ParamCounter) -> #core::Clause {
+                let dialect = counter.dialect();
+                let mut values = #core::Clause::new();
+                let mut index = counter.next_index();
+                let mut placeholders = 0;
+                #(#values)*
+                counter.advance(index.max(#max_index), placeholders);
+                values
+            }
+            fn next_param_index() -> usize {
+                #next_index
+            }
+            fn placeholder_map() -> &'static [(usize, &'static str)] {
+                #placeholder_map
+            }
+            fn generate_values_rows(n: usize) -> Vec<String> {
+                Self::generate_values_rows_for(n, #dialect)
+            }
+            fn generate_values_row_fragments(row: usize, dialect: #core::Dialect) -> #core::Clause {
+                let offset = row * #stride;
+                let mut values = #core::Clause::new();
+                let mut index = #index + offset;
+                #(#row_values)*
+                values
+            }
+            fn rows_per_statement(dialect: #core::Dialect) -> usize {
+                let (before, per_row) = if dialect.numbered_placeholders() {
+                    (#before, #stride)
+                } else {
+                    (0, #row_placeholders)
+                };
+                let max_rows = dialect.max_rows().unwrap_or(usize::MAX);
+                if per_row == 0 {
+                    return max_rows;
+                }
+                (dialect.max_parameters().saturating_sub(before) / per_row).clamp(1, max_rows)
             }
-            // fn last_param_index() -> usize {
-            //     #index
-            // }
         }
-    };
 
-    TokenStream::from(expanded)
+        #sqlx_impl
+        #rusqlite_impl
+        #postgres_impl
+    })
 }
@@ -1,280 +1,826 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
-// use std::any::Any;
 
 use quote::{quote, quote_spanned};
+use sql_tool_core::{Dialect, LikeMode};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Data, DeriveInput, Expr, Lit, Meta, Token, Type};
+use syn::{parse_macro_input, DeriveInput};
 
-use crate::macro_utils::generate_placeholder;
-use syn::punctuated::Punctuated;
+use crate::bind::{BindArgs, BindField, BindValue};
+use crate::macro_utils::{
+    alias_tokens, bind_args, config_alias, core_path, dialect_tokens, field_ident, find_attribute,
+    fragment_tokens, into_token_stream, is_nullable, is_option, is_plain_identifier, meta_name,
+    name_value_to_bool, name_value_to_dialect, name_value_to_string, name_value_to_usize,
+    named_fields, nullable_tokens, parse_nested, quote_column, render_template, require_name_value,
+    require_path, unknown_meta, ColumnName, Errors,
+};
 
-///
+/// 生成针对特定结构体的 `WhereAttributeMacro` 实现。
 pub fn gen_where_attribute_impl(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
+    into_token_stream(expand(&input))
+}
+
+/// `#[r#where(...)]` 解析后的结果，供 `GenWhere` 和 `GenDelete` 共用
+pub struct WhereClause {
+    /// `#[config(database = "...")]` 设置的数据库
+    pub dialect: Dialect,
+    /// 占位符的起始索引
+    pub index: usize,
+    /// `#[config(alias = "...")]` 设置的表别名
+    pub alias: Option<String>,
+    /// 每个字段生成的代码块，代码块会根据 `dialect` 向 `fields` 和 `args` 中写入数据，并更新 `index`。
+    ///
+    /// 存在设置了 `index` 的字段时，代码块依赖 `binds.slots_tokens()` 生成的变量
+    pub values: Vec<proc_macro2::TokenStream>,
+    /// 生成了占位符的字段，用于生成绑定参数的代码
+    pub binds: BindArgs,
+}
+
+/// 解析结构体上的 `#[config(...)]` 以及字段上的 `#[r#where(...)]`
+pub fn where_clause(input: &DeriveInput) -> syn::Result<WhereClause> {
+    let core = core_path();
     let name = &input.ident;
+    let mut errors = Errors::default();
 
     let mut index = 1;
-    let mut placeholder = String::new();
+    let mut dialect = Dialect::Postgres;
+    let mut has_database = false;
     let mut ignore_none = true;
     let mut ignore_no_macro_where = true;
+    let mut quote_identifiers = false;
+    let mut join = "AND";
 
-    let attrs = input
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("config"))
-        .expect("必须在结构上设置 `#[config(database = \"/*数据库类型*/\")]` 宏");
+    let attrs = find_attribute(&input.attrs, "config").ok_or_else(|| {
+        syn::Error::new_spanned(
+            name,
+            "必须在结构上设置 `#[config(database = \"/*数据库类型*/\")]` 宏",
+        )
+    })?;
 
-    let nested = attrs
-        .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-        .map_err(|e| {
-            println!("分析字段属性时出错");
-            e
-        })
-        .unwrap();
-
-    for meta in nested {
-        match meta {
-            Meta::NameValue(name_value) if meta.path().is_ident("database") => {
-                if let Expr::Lit(value) = &name_value.value {
-                    if let Lit::Str(value) = &value.lit {
-                        placeholder = generate_placeholder(value.value().as_str());
-                        continue;
-                    }
+    for meta in parse_nested(attrs)? {
+        match meta_name(&meta).as_str() {
+            "database" => {
+                has_database = true;
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_dialect))
+                {
+                    dialect = value;
                 }
-                panic!("database 值转换失败")
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("ignore_none") => {
-                if let Expr::Lit(value) = &name_value.value {
-                    if let Lit::Bool(value) = &value.lit {
-                        ignore_none = value.value;
-                    }
+            "ignore_none" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
+                    ignore_none = value;
                 }
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("index") => {
-                if let Expr::Lit(value) = &name_value.value {
-                    if let Lit::Int(value) = &value.lit {
-                        index = value.base10_parse::<usize>().unwrap();
-                    }
+            "index" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_usize))
+                {
+                    index = value;
                 }
             }
-            Meta::NameValue(name_value) if meta.path().is_ident("ignore_no_macro_where") => {
-                if let Expr::Lit(value) = &name_value.value {
-                    if let Lit::Bool(value) = &value.lit {
-                        ignore_no_macro_where = value.value;
-                    }
+            "ignore_no_macro_where" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
+                    ignore_no_macro_where = value;
+                }
+            }
+            "quote_identifiers" => {
+                if let Some(value) =
+                    errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                {
+                    quote_identifiers = value;
+                }
+            }
+            "join" => {
+                if let Some(value) = errors.handle(require_name_value(&meta).and_then(parse_join)) {
+                    join = value;
                 }
             }
+            // `#[config(...)]` 可能同时被其他派生宏使用，这里忽略其他参数
             _ => {}
         }
     }
 
-    if placeholder.is_empty() {
-        panic!("database 值必须设置");
+    if !has_database {
+        errors.push_spanned(attrs, "`database` 值必须设置");
     }
 
-    let values = if let Data::Struct(data_struct) = &input.data {
-        let mut fields = Vec::new();
-        for field in &data_struct.fields {
-            let mut where_value = Some("{name} {condition} {index}".to_string());
-            let field_name = field.ident.as_ref().unwrap().to_string();
-            let field_value = field.ident.clone().unwrap();
+    let mut entries = Vec::new();
+    let mut groups: Vec<(String, Option<&'static str>)> = Vec::new();
+    let mut list_fields = Vec::new();
+    let mut index_fields = Vec::new();
+    for field in named_fields(input)? {
+        let mut where_value = Some("{name} {condition} {index}".to_string());
+        let mut field_name = field_ident(field).to_string();
+        let field_value = field_ident(field);
 
-            let attrs = field
-                .attrs
-                .iter()
-                .find(|attr| attr.path().is_ident("r#where"));
+        let attrs = find_attribute(&field.attrs, "r#where");
 
-            let mut ignore_none = ignore_none;
-            let mut condition_all = String::new();
-            let mut condition = "=".to_string();
-            let mut rename = String::new();
-            let mut value_placeholder = String::new();
-            let mut field_index = -1;
-            let mut add_index: usize = 0;
+        let mut ignore_none = ignore_none;
+        let mut condition_all = String::new();
+        let mut condition = "=".to_string();
+        let mut rename = String::new();
+        let mut value_placeholder = String::new();
+        let mut field_index = None;
+        let mut quote = None;
+        let mut add_index: usize = 0;
+        let mut placeholder_count = 0;
+        let mut expression = false;
+        let mut group = None;
+        let mut group_join = None;
+        let mut not = false;
+        let mut in_list = false;
+        let mut any = false;
+        let mut range = false;
+        let mut not_null = false;
+        let nullable = is_nullable(&field.ty);
+        let mut empty = None;
+        let mut has_condition = false;
+        let mut like = None;
+        let mut case_insensitive = false;
+        // 不区分大小写的 `like` 在 postgres 中使用 `ILIKE`，其余数据库使用 `LOWER`
+        let mut postgres_value = None;
 
-            if let Some(attr) = attrs {
-                let nested = attr
-                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-                    .map_err(|e| {
-                        println!("分析 'value' 属性时出错");
-                        e
-                    })
-                    .unwrap();
+        if let Some(attr) = attrs {
+            let Some(nested) = errors.handle(parse_nested(attr)) else {
+                continue;
+            };
 
-                for meta in nested {
-                    match meta {
-                        Meta::Path(_) if meta.path().is_ident("ignore") => {
+            for meta in nested {
+                match meta_name(&meta).as_str() {
+                    "ignore" => {
+                        if errors.handle(require_path(&meta)).is_some() {
                             where_value = None;
-                            break;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("ignore_none") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Bool(val) = &value.lit {
-                                    ignore_none = val.value();
-                                }
-                            }
+                    }
+                    "ignore_none" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                        {
+                            ignore_none = value;
+                        }
+                    }
+                    "condition_all" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            condition_all = value;
+                        }
+                    }
+                    "rename" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            rename = value;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("condition_all") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Str(val) = &value.lit {
-                                    condition_all = val.value();
-                                }
-                            }
+                    }
+                    "condition" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            condition = value;
+                            has_condition = true;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("rename") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Str(val) = &value.lit {
-                                    rename = val.value();
-                                }
-                            }
+                    }
+                    "value" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string))
+                        {
+                            value_placeholder = value;
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("condition") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Str(val) = &value.lit {
-                                    condition = val.value();
-                                }
-                            }
+                    }
+                    "index" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_usize))
+                        {
+                            field_index = Some(value);
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("value") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Str(val) = &value.lit {
-                                    value_placeholder = val.value();
-                                }
-                            }
+                    }
+                    "quote" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_bool))
+                        {
+                            quote = Some(value);
                         }
-                        Meta::NameValue(name_value) if meta.path().is_ident("index") => {
-                            if let Expr::Lit(value) = &name_value.value {
-                                if let Lit::Int(val) = &value.lit {
-                                    field_index = val.base10_parse::<i32>().unwrap();
-                                }
-                            }
+                    }
+                    "group" => {
+                        group =
+                            errors.handle(require_name_value(&meta).and_then(name_value_to_string));
+                    }
+                    "join" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(parse_join))
+                        {
+                            group_join = Some((value, meta.clone()));
+                        }
+                    }
+                    "not" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            not = true;
+                        }
+                    }
+                    "in" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            in_list = true;
+                        }
+                    }
+                    "any" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            any = true;
+                        }
+                    }
+                    "not_null" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            not_null = true;
+                        }
+                    }
+                    "range" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            range = true;
                         }
-                        _ => {}
                     }
+                    "like" => {
+                        like = errors.handle(require_name_value(&meta).and_then(parse_like));
+                    }
+                    "case_insensitive" => {
+                        if errors.handle(require_path(&meta)).is_some() {
+                            case_insensitive = true;
+                        }
+                    }
+                    "empty" => {
+                        if let Some(value) =
+                            errors.handle(require_name_value(&meta).and_then(parse_empty))
+                        {
+                            empty = Some((value, meta.clone()));
+                        }
+                    }
+                    _ => errors.push(unknown_meta(&meta, "r#where")),
                 }
-                if let Some(mut field) = where_value {
-                    // 使用 condition_all 或原始 field
-                    field = if !condition_all.is_empty() {
-                        condition_all
-                    } else {
-                        field
-                    };
+            }
+            match (&group, group_join.take()) {
+                (None, Some((_, meta))) => {
+                    errors.push_spanned(meta, "`join` 只能与 `group` 一起使用");
+                }
+                (Some(group), join) => match groups.iter_mut().find(|(name, _)| name == group) {
+                    Some((_, existing)) => match (existing, join) {
+                        (Some(existing), Some((value, meta))) if *existing != value => {
+                            errors.push_spanned(
+                                    meta,
+                                    format!(
+                                        "分组 `{}` 已经使用了 `join = \"{}\"`，同一个分组只能使用一种连接方式",
+                                        group, existing
+                                    ),
+                                );
+                        }
+                        (existing @ None, Some((value, _))) => *existing = Some(value),
+                        _ => {}
+                    },
+                    None => groups.push((group.clone(), join.map(|(value, _)| value))),
+                },
+                (None, None) => {}
+            }
+            if in_list || any {
+                let name = if in_list { "in" } else { "any" };
+                let conflicts = [
+                    (in_list && any, "any"),
+                    (!condition_all.is_empty(), "condition_all"),
+                    (has_condition, "condition"),
+                    (!value_placeholder.is_empty(), "value"),
+                    (field_index.is_some(), "index"),
+                ];
+                for (_, other) in conflicts.iter().filter(|(conflict, _)| *conflict) {
+                    errors.push_spanned(attr, format!("`{}` 不能与 `{}` 一起使用", name, other));
+                }
+                if any && dialect != Dialect::Postgres {
+                    errors.push_spanned(attr, "`any` 只能在 `database = \"postgres\"` 时使用");
+                }
+                // `{list}` 在运行时替换为所有元素的占位符，
+                // `any` 只在运行时的数据库为 postgres 时生成 `= ANY(...)`，其余数据库同样展开为 `IN`
+                where_value = where_value.map(|_| "{name} IN ({list})".to_string());
+            }
+            if range {
+                let conflicts = [
+                    (in_list, "in"),
+                    (any, "any"),
+                    (like.is_some(), "like"),
+                    (!condition_all.is_empty(), "condition_all"),
+                    (has_condition, "condition"),
+                    (!value_placeholder.is_empty(), "value"),
+                    (field_index.is_some(), "index"),
+                ];
+                for (_, other) in conflicts.iter().filter(|(conflict, _)| *conflict) {
+                    errors.push_spanned(attr, format!("`range` 不能与 `{}` 一起使用", other));
+                }
+                // 范围条件在运行时根据存在的边界生成，这里只替换 `{name}`
+                where_value = where_value.map(|_| "{name}".to_string());
+            } else if like.is_some() {
+                let conflicts = [
+                    (in_list, "in"),
+                    (any, "any"),
+                    (!condition_all.is_empty(), "condition_all"),
+                    (has_condition, "condition"),
+                    (!value_placeholder.is_empty(), "value"),
+                ];
+                for (_, other) in conflicts.iter().filter(|(conflict, _)| *conflict) {
+                    errors.push_spanned(attr, format!("`like` 不能与 `{}` 一起使用", other));
+                }
+                let escape = LikeMode::ESCAPE;
+                let template = if case_insensitive {
+                    postgres_value = Some(format!("{{name}} ILIKE {{index}} ESCAPE '{}'", escape));
+                    format!("LOWER({{name}}) LIKE LOWER({{index}}) ESCAPE '{}'", escape)
+                } else {
+                    format!("{{name}} LIKE {{index}} ESCAPE '{}'", escape)
+                };
+                where_value = where_value.map(|_| template);
+            } else if case_insensitive {
+                errors.push_spanned(attr, "`case_insensitive` 只能与 `like` 一起使用");
+            }
+            if nullable {
+                let conflicts = [
+                    (in_list, "in"),
+                    (any, "any"),
+                    (range, "range"),
+                    (like.is_some(), "like"),
+                ];
+                for (_, other) in conflicts.iter().filter(|(conflict, _)| *conflict) {
+                    errors.push_spanned(
+                        attr,
+                        format!("`Option<Option<T>>` 字段不能使用 `{}`", other),
+                    );
+                }
+            } else if not_null {
+                errors.push_spanned(attr, "`not_null` 只能用于 `Option<Option<T>>` 字段");
+            }
+            if let (Some((_, meta)), false) = (&empty, in_list) {
+                errors.push_spanned(meta, "`empty` 只能与 `in` 一起使用");
+            }
+            if let Some(mut field) = where_value {
+                expression = !condition_all.is_empty() || !value_placeholder.is_empty();
+                // 使用 condition_all 或原始 field
+                field = if !condition_all.is_empty() {
+                    condition_all
+                } else {
+                    field
+                };
 
-                    // 替换 {name}
-                    let name_to_use = if rename.is_empty() {
-                        &field_name
-                    } else {
-                        &rename
-                    };
-                    field = field.replace("{name}", name_to_use);
+                // {name} 在生成代码时替换，需要引号或表别名时在运行时替换
+                if !rename.is_empty() {
+                    field_name = rename;
+                }
 
-                    // 检查并替换 {condition}
-                    if condition.is_empty() && field.contains("{condition}") {
-                        panic!(
+                // 检查并替换 {condition}
+                if condition.is_empty() && field.contains("{condition}") {
+                    errors.push_spanned(
+                        attr,
+                        format!(
                             "存在 {{condition}} 但是在字段 {} 的属性宏上没有设置 condition 属性",
                             field_name
-                        );
-                    }
-                    field = field.replace("{condition}", &condition);
-
-                    // 替换 {index} 和 {value}
-                    let index_str = if field_index != -1 {
-                        field_index.to_string()
-                    } else {
-                        "{index}".to_string()
-                    };
-
-                    let placeholder_str = if value_placeholder.is_empty() {
-                        placeholder.replace("{index}", &index_str)
-                    } else {
-                        value_placeholder.clone()
-                    };
-
-                    field = field.replace("{index}", &placeholder_str);
-
-                    where_value = Some(field);
+                        ),
+                    );
                 }
-            } else {
-                if let (false, Some(mut value)) = (ignore_no_macro_where, where_value.clone()) {
-                    value = value.replace("{name}", &field_name);
-                    value = value.replace("{condition}", &condition);
-                    value = value.replace("{index}", &placeholder.replace("{index}", "{index}"));
-                    where_value = Some(value);
+                field = field.replace("{condition}", &condition);
+
+                // 使用 `value` 时直接替换 {index}，不会绑定参数
+                if value_placeholder.is_empty() {
+                    placeholder_count = field.matches("{index}").count();
                 } else {
-                    where_value = None;
+                    field = field.replace("{index}", &value_placeholder);
                 }
+
+                where_value = Some(field);
             }
-            if field_index == -1 && value_placeholder.is_empty() {
+        } else if let (false, Some(mut value)) = (ignore_no_macro_where, where_value.clone()) {
+            value = value.replace("{condition}", &condition);
+            placeholder_count = 1;
+            where_value = Some(value);
+        } else {
+            where_value = None;
+        }
+        if let Some(value) = where_value {
+            // 使用 `value` 替换占位符或者指定了 `index` 时，全局的 index 不会加一
+            if field_index.is_none() && placeholder_count > 0 {
                 add_index = 1;
             }
-            if let Some(value) = where_value {
-                let get_data = if let Type::Path(type_path) = &field.ty {
-                    if ignore_none
-                        && type_path
-                            .path
-                            .segments
-                            .first()
-                            .map_or(false, |segment| segment.ident == "Option")
+            // 设置了 `index` 的字段同样使用 `index` 渲染占位符，由 `BindField::renumber_tokens` 设置
+            let quote = quote_column(quote, quote_identifiers, &field_name);
+            let column = ColumnName::new(&field_name, quote, true);
+            let mut render = render_template(&value, None, Some(&column));
+            if let Some(postgres_value) = &postgres_value {
+                let postgres = render_template(postgres_value, None, Some(&column));
+                render = quote! {
+                    if dialect == #core::Dialect::Postgres {
+                        #postgres
+                    } else {
+                        #render
+                    }
+                };
+            }
+            if in_list || any {
+                render = quote! { #render.replace("{list}", &placeholders.join(", ")) };
+            }
+            // 范围字段的 `render` 为字段名，生成的条件保存在运行时的 `condition` 中
+            let column_render = range.then(|| std::mem::replace(&mut render, quote! { condition }));
+            if not {
+                render = quote! { format!("NOT ({})", #render) };
+            }
+            let placeholder = quote! { index };
+            let field_str = field_ident(field).to_string();
+            let fragment = fragment_tokens(
+                render,
+                &field_str,
+                (placeholder_count > 0 || in_list || any || range).then_some(placeholder),
+                expression || not || range || !is_plain_identifier(&field_name),
+            );
+            // `Option<Option<T>>` 字段的 `None` 总是跳过，由 `nullable_tokens` 处理
+            let optional = ignore_none && is_option(&field.ty) && !nullable;
+            let (push_data, bind) = if let Some(column_render) = column_render {
+                list_fields.push((field_value.clone(), "range"));
+                let push_data = quote! {
+                    let column: String = #column_render;
+                    if let Some(condition) =
+                        #core::RangeValue::range_condition(&self.#field_value, &column, dialect, index)
                     {
-                        quote! {
-                            if self.#field_value.is_some() {
-                                (Some(#value), #add_index)
-                            } else {
-                                (None, #add_index)
-                            }
+                        fields.push(#fragment);
+                        let (start, end) = #core::RangeValue::bounds(&self.#field_value);
+                        for bound in [start, end].into_iter().flatten() {
+                            args.push(#core::SqlArg::Ref(bound));
+                            index += 1;
                         }
+                    }
+                };
+                let bind = BindField {
+                    ident: field_value.clone(),
+                    optional,
+                    index: None,
+                    add_index: 1,
+                    count: 1,
+                    value: BindValue::Range,
+                };
+                (push_data, Some(bind))
+            } else if in_list || any {
+                list_fields.push((field_value.clone(), if any { "any" } else { "in" }));
+                let list = if is_option(&field.ty) {
+                    quote! { self.#field_value.as_deref().unwrap_or_default() }
+                } else {
+                    quote! { &self.#field_value[..] }
+                };
+                // 空列表默认生成恒为假的条件，设置了 `empty = "skip"` 时跳过该条件
+                let push_empty = match empty {
+                    Some((true, _)) => quote! {},
+                    _ => {
+                        let text = if not { "NOT (1 = 0)" } else { "1 = 0" };
+                        quote! { fields.push(#core::SqlFragment::new(#text, #field_str).with_expression()); }
+                    }
+                };
+                // 运行时的数据库为 postgres 时，`any` 将整个字段作为一个数组绑定
+                let postgres_any = any.then(|| {
+                    let mut render = render_template("{name} = ANY({index})", None, Some(&column));
+                    if not {
+                        render = quote! { format!("NOT ({})", #render) };
+                    }
+                    let fragment = fragment_tokens(
+                        render,
+                        &field_str,
+                        Some(quote! { index }),
+                        not || !is_plain_identifier(&field_name),
+                    );
+                    quote! {
+                        if dialect == #core::Dialect::Postgres {
+                            fields.push(#fragment);
+                            args.push(#core::SqlArg::Ref(&self.#field_value));
+                            index += 1;
+                        } else
+                    }
+                });
+                let push_data = quote! {
+                    let list: &[_] = #list;
+                    #postgres_any
+                    if list.is_empty() {
+                        #push_empty
                     } else {
-                        quote_spanned! {field.span() => (Some(#value), #add_index)}
+                        let placeholders: Vec<String> = (index..index + list.len())
+                            .map(|index| dialect.placeholder(index))
+                            .collect();
+                        fields.push(#fragment);
+                        for item in list {
+                            args.push(#core::SqlArg::Ref(item));
+                        }
+                        index += list.len();
                     }
+                };
+                let value = if any {
+                    BindValue::Any(list)
                 } else {
-                    quote_spanned! {field.span() => (Some(#value), #add_index)}
+                    BindValue::List(list)
                 };
-                // let get_data = quote_spanned!{field.span() => Some(#value)};
-                fields.push(get_data);
+                let bind = BindField {
+                    ident: field_value.clone(),
+                    optional,
+                    index: None,
+                    add_index: 1,
+                    count: 1,
+                    value,
+                };
+                (push_data, Some(bind))
+            } else {
+                let args = match &like {
+                    Some(mode) => quote! {
+                        args.push(#core::SqlArg::Owned(Box::new(#core::LikeValue::to_pattern(
+                            &self.#field_value,
+                            #mode,
+                            dialect,
+                        ))));
+                    },
+                    None if nullable => bind_args(quote! { value }, placeholder_count),
+                    None => bind_args(quote! { &self.#field_value }, placeholder_count),
+                };
+                let push_data = quote! {
+                    fields.push(#fragment);
+                    index += #add_index;
+                    #args
+                };
+                let bind = (placeholder_count > 0).then(|| BindField {
+                    ident: field_value.clone(),
+                    optional,
+                    index: field_index,
+                    add_index,
+                    count: placeholder_count,
+                    value: match &like {
+                        Some(mode) => BindValue::Like(mode.clone()),
+                        None if nullable => BindValue::Nullable,
+                        None => BindValue::Field,
+                    },
+                });
+                (push_data, bind)
+            };
+            if let Some(field_index) = field_index.filter(|_| placeholder_count > 0) {
+                index_fields.push((field_value.clone(), field_index));
+            }
+            let get_data = if nullable {
+                // `Some(None)` 时生成 `IS NULL`，不会占用占位符
+                let template = if not_null {
+                    "{name} IS NOT NULL"
+                } else {
+                    "{name} IS NULL"
+                };
+                let mut render = render_template(template, None, Some(&column));
+                if not {
+                    render = quote! { format!("NOT ({})", #render) };
+                }
+                let fragment = fragment_tokens(
+                    render,
+                    &field_str,
+                    None,
+                    not || !is_plain_identifier(&field_name),
+                );
+                nullable_tokens(field_value, quote! { fields.push(#fragment); }, push_data)
+            } else if optional {
+                quote! {
+                    if self.#field_value.is_some() {
+                        #push_data
+                    }
+                }
+            } else {
+                quote_spanned! {field.span() => { #push_data }}
+            };
+            entries.push((group, get_data, bind));
+        }
+    }
+    // 列表的元素个数以及范围存在的边界在运行时才能确定，之后的占位符序号无法在编译期指定
+    if let (Some((list, name)), Some((ident, field_index))) =
+        (list_fields.first(), index_fields.first())
+    {
+        errors.push_spanned(
+            ident,
+            format!(
+                "字段 `{}` 使用了 `{}`，占位符的个数在运行时确定，不能再通过 `index = {}` 指定序号",
+                list, name, field_index
+            ),
+        );
+    }
+    // 存在设置了 `index` 的字段时，跳过的字段会改变之后的序号，在运行时重新编号
+    if !index_fields.is_empty() {
+        for (_, block, bind) in entries.iter_mut() {
+            if let Some(bind) = bind {
+                *block = bind.renumber_tokens(std::mem::take(block));
             }
         }
-        fields
-    } else {
-        Vec::new()
+    }
+    let (values, binds) = arrange_groups(entries, &groups, join);
+    let alias = errors.handle(config_alias(input)).flatten();
+    let binds = errors.handle(BindArgs::renumbered(dialect, index, binds));
+    errors.finish()?;
+
+    let Some(binds) = binds else {
+        unreachable!("存在错误时已经提前返回");
     };
 
-    let expanded = quote! {
-        // 在此次声明 index 呢
-        impl WhereAttributeMacro for #name {
-            fn generate_where_clause(&self) -> Vec<String> {
-                let mut fields = Vec::new();
+    Ok(WhereClause {
+        dialect,
+        index,
+        alias,
+        values,
+        binds,
+    })
+}
+
+/// 解析 `empty = "false"` 或 `empty = "skip"`，返回空列表时是否跳过该条件
+fn parse_empty(name_value: &syn::MetaNameValue) -> syn::Result<bool> {
+    match name_value_to_string(name_value)?.as_str() {
+        "false" => Ok(false),
+        "skip" => Ok(true),
+        _ => Err(syn::Error::new_spanned(
+            &name_value.value,
+            "`empty` 的值只能是 \"false\" 或 \"skip\"",
+        )),
+    }
+}
+
+/// 解析 `like = "contains"`、`like = "prefix"` 或 `like = "suffix"`，返回生成代码中对应的 `LikeMode`
+fn parse_like(name_value: &syn::MetaNameValue) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
+    match name_value_to_string(name_value)?.as_str() {
+        "contains" => Ok(quote! { #core::LikeMode::Contains }),
+        "prefix" => Ok(quote! { #core::LikeMode::Prefix }),
+        "suffix" => Ok(quote! { #core::LikeMode::Suffix }),
+        _ => Err(syn::Error::new_spanned(
+            &name_value.value,
+            "`like` 的值只能是 \"contains\"、\"prefix\" 或 \"suffix\"",
+        )),
+    }
+}
+
+/// 解析 `join = "AND"` 或 `join = "OR"`，不区分大小写
+fn parse_join(name_value: &syn::MetaNameValue) -> syn::Result<&'static str> {
+    match name_value_to_string(name_value)?.to_uppercase().as_str() {
+        "AND" => Ok("AND"),
+        "OR" => Ok("OR"),
+        _ => Err(syn::Error::new_spanned(
+            &name_value.value,
+            "`join` 的值只能是 \"AND\" 或 \"OR\"",
+        )),
+    }
+}
+
+/// 按照分组整理每个字段生成的代码块，返回整理后的代码块以及需要绑定参数的字段。
+///
+/// 同一个分组的字段会移动到该分组第一个字段的位置，合并为一个带括号的片段，没有设置 `join` 的分组使用 `join`。
+/// `join` 为 `OR` 时所有条件再合并为一个片段，这样调用方仍然可以使用 `" AND "` 拼接。
+/// 占位符按照整理后的顺序生成，保证使用 `?` 的数据库绑定的顺序与 SQL 中出现的顺序一致。
+fn arrange_groups(
+    entries: Vec<(Option<String>, proc_macro2::TokenStream, Option<BindField>)>,
+    groups: &[(String, Option<&'static str>)],
+    join: &'static str,
+) -> (Vec<proc_macro2::TokenStream>, Vec<BindField>) {
+    let mut entries: Vec<_> = entries.into_iter().map(Some).collect();
+    let mut values = Vec::new();
+    let mut binds = Vec::new();
+    for position in 0..entries.len() {
+        let Some((group, block, bind)) = entries[position].take() else {
+            continue;
+        };
+        binds.extend(bind);
+        let Some(group) = group else {
+            values.push(block);
+            continue;
+        };
+        let mut blocks = vec![block];
+        for entry in entries[position + 1..].iter_mut() {
+            if entry
+                .as_ref()
+                .is_some_and(|(other, _, _)| other.as_ref() == Some(&group))
+            {
+                let (_, block, bind) = entry.take().expect("已经检查过");
+                blocks.push(block);
+                binds.extend(bind);
+            }
+        }
+        let group_join = groups
+            .iter()
+            .find(|(name, _)| *name == group)
+            .and_then(|(_, join)| *join)
+            .unwrap_or(join);
+        values.push(group_tokens(&blocks, group_join, &group));
+    }
+    if join == "OR" {
+        values = vec![group_tokens(&values, join, "")];
+    }
+    (values, binds)
+}
+
+/// 生成将 `blocks` 写入的片段合并为一个片段的代码
+fn group_tokens(
+    blocks: &[proc_macro2::TokenStream],
+    join: &str,
+    group: &str,
+) -> proc_macro2::TokenStream {
+    quote! {{
+        let outer = std::mem::take(&mut fields);
+        #(#blocks)*
+        let group = std::mem::replace(&mut fields, outer);
+        fields.extend(group.group(#join, #group));
+    }}
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let core = core_path();
+    let name = &input.ident;
+    let WhereClause {
+        dialect,
+        index,
+        alias,
+        values,
+        binds,
+    } = where_clause(input)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let sqlx_impl = binds.sqlx_impl(input, "SqlxWhereArgs", "bind_where_args");
+    let rusqlite_impl = binds.rusqlite_impl(input, "RusqliteWhereParams");
+    let postgres_impl = binds.postgres_impl(input, "PgWhereParams");
+    let placeholder_map = binds.map_tokens();
+    let ordered_args = binds.args_tokens();
+    let slots = binds.slots_tokens();
+    let next_index = binds.next_index_tokens();
+    let dialect = dialect_tokens(dialect);
+    let alias = alias_tokens(alias.as_deref());
+
+    Ok(quote! {
+        impl #impl_generics #core::WhereAttributeMacro for #name #ty_generics #where_clause {
+            fn generate_where_clause_for(&self, dialect: #core::Dialect) -> Vec<String> {
+                self.generate_where_clause_with_index_for(#index, dialect)
+            }
+            fn generate_where_clause_with_index(&self, index: usize) -> Vec<String> {
+                self.generate_where_clause_with_index_for(index, #dialect)
+            }
+            fn generate_where_clause_with_index_for(
+                &self,
+                index: usize,
+                dialect: #core::Dialect,
+            ) -> Vec<String> {
+                self.generate_where_clause_with_counter(&mut #core::ParamCounter::with_start(dialect, index))
+            }
+            fn generate_where_fragments(&self) -> #core::Clause {
+                self.generate_where_fragments_with_counter(&mut #core::ParamCounter::with_start(#dialect, #index))
+            }
+            fn generate_where_fragments_with_alias(&self, alias: &str) -> #core::Clause {
+                let mut fields = #core::Clause::new();
+                let mut args: Vec<#core::SqlArg<'_>> = Vec::new();
                 let mut index = #index;
-                #(
-                    // 使用 values 中的每个 TokenStream
-                    if let (Some(value), add_index) = #values {
-                        let value = value.replace("{index}", &index.to_string());
-                        index = index + add_index;
-                        fields.push(value);
-                    }
-                )*
+                let dialect = #dialect;
+                let alias = Some(alias);
+                #slots
+                #(#values)*
                 fields
             }
-            fn generate_where_clause_with_index(&self, index: usize) -> Vec<String> {
-                let mut fields = Vec::new();
-                let mut index = index;
-                #(
-                    // 使用 values 中的每个 TokenStream
-                    if let (Some(value), add_index) = #values {
-                        let value = value.replace("{index}", &index.to_string());
-                        index = index + add_index;
-                        fields.push(value);
-                    }
-                )*
+            fn placeholder_map(&self) -> &'static [(usize, &'static str)] {
+                #placeholder_map
+            }
+            fn next_param_index(&self) -> usize {
+                self.next_param_index_with_index(#index)
+            }
+            fn next_param_index_with_index(&self, index: usize) -> usize {
+                let mut counter = #core::ParamCounter::with_start(#dialect, index);
+                self.generate_where_clause_with_counter(&mut counter);
+                counter.next_index()
+            }
+            fn generate_where_fragments_with_counter(&self, counter: &mut #core::ParamCounter) -> #core::Clause {
+                let dialect = counter.dialect();
+                let mut fields = #core::Clause::new();
+                let mut args: Vec<#core::SqlArg<'_>> = Vec::new();
+                let mut index = counter.next_index();
+                let alias: Option<&str> = #alias;
+                #slots
+                #(#values)*
+                #next_index
+                counter.advance(index, args.len());
                 fields
             }
-            // fn last_param_index(&self) -> usize {
-            //     #index
-            // }
+            fn generate_where_clause_with_args(&self) -> (Vec<String>, Vec<#core::SqlArg<'_>>) {
+                self.generate_where_clause_with_args_for(#dialect)
+            }
+            fn generate_where_clause_with_args_for(
+                &self,
+                dialect: #core::Dialect,
+            ) -> (Vec<String>, Vec<#core::SqlArg<'_>>) {
+                let mut fields = #core::Clause::new();
+                let mut args: Vec<#core::SqlArg<'_>> = Vec::new();
+                let mut index = #index;
+                let alias: Option<&str> = #alias;
+                #slots
+                #(#values)*
+                // 带序号的占位符按照序号排列参数
+                (fields.into_strings(), #ordered_args)
+            }
         }
-    };
 
-    TokenStream::from(expanded)
+        #sqlx_impl
+        #rusqlite_impl
+        #postgres_impl
+    })
 }
 
 // {
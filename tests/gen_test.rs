@@ -332,3 +332,1518 @@ fn set_test() {
     ];
     assert_eq!(set_data, value.generate_set_clause());
 }
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "mysql")]
+pub struct MysqlWhereStruct {
+    #[r#where(condition_all = "(title like {index} OR subtitle like {index})")]
+    pub keyword: Option<String>,
+    #[r#where(condition = ">=", value = "now()")]
+    pub start_time: Option<String>,
+    #[r#where(condition = "<=")]
+    pub end_time: Option<String>,
+    #[r#where()]
+    pub ty: i32,
+}
+
+#[test]
+fn where_with_args_test() {
+    let value = PgWhereStruct {
+        keyword: Some("这是标题".to_string()),
+        start_time: None,
+        end_time: Some("2024/12/12".to_string()),
+        ty: 1,
+        page_info: 0,
+    };
+    let (fields, args) = value.generate_where_clause_with_args();
+    assert_eq!(fields, value.generate_where_clause());
+    assert_eq!(args.len(), 3);
+    assert_eq!(
+        args[0].downcast_ref::<Option<String>>(),
+        Some(&value.keyword)
+    );
+    assert_eq!(
+        args[1].downcast_ref::<Option<String>>(),
+        Some(&value.end_time)
+    );
+    assert_eq!(args[2].downcast_ref::<i32>(), Some(&1));
+
+    let value = MysqlWhereStruct {
+        keyword: Some("这是标题".to_string()),
+        start_time: Some("2024/12/12".to_string()),
+        end_time: None,
+        ty: 2,
+    };
+    let (fields, args) = value.generate_where_clause_with_args();
+    let data = vec![
+        "(title like ? OR subtitle like ?)".to_string(),
+        "start_time >= now()".to_string(),
+        "ty = ?".to_string(),
+    ];
+    assert_eq!(data, fields);
+    // 每个 `?` 都对应一个参数，`value` 替换的字段不会绑定参数
+    assert_eq!(args.len(), 3);
+    assert_eq!(
+        args[0].downcast_ref::<Option<String>>(),
+        Some(&value.keyword)
+    );
+    assert_eq!(
+        args[1].downcast_ref::<Option<String>>(),
+        Some(&value.keyword)
+    );
+    assert_eq!(args[2].downcast_ref::<i32>(), Some(&2));
+}
+
+#[cfg(test)]
+#[derive(GenInsert)]
+#[config(table = "advert", database = "postgres")]
+pub struct PgInsertStruct {
+    pub title: String,
+    #[value(index = 1)]
+    pub subtitle: Option<String>,
+    pub image_url: String,
+    #[value(value = "now()")]
+    pub start_time: String,
+    #[field(rename = "type")]
+    pub ty: i32,
+    #[field(ignore)]
+    #[value(ignore)]
+    pub page_info: usize,
+}
+
+#[cfg(test)]
+#[derive(GenInsert, GenFields, GenValues)]
+#[config(table = "advert", database = "mysql")]
+pub struct MysqlInsertStruct {
+    pub title: String,
+    #[value(value = "now()")]
+    pub start_time: String,
+    #[field(rename = "type")]
+    pub ty: i32,
+}
+
+// `GenInsert` 不会为结构体添加固有方法，不会与同名的方法冲突
+#[cfg(test)]
+impl MysqlInsertStruct {
+    pub fn insert_clauses(&self) -> usize {
+        self.ty as usize
+    }
+}
+
+#[test]
+fn insert_test() {
+    assert_eq!(
+        "INSERT INTO advert (title, subtitle, image_url, start_time, type) VALUES ($1, $1, $2, now(), $3)",
+        PgInsertStruct::generate_insert_sql()
+    );
+    assert_eq!(
+        format!(
+            "INSERT INTO advert ({}) VALUES ({})",
+            MysqlInsertStruct::generate_fields_clause().join(", "),
+            MysqlInsertStruct::generate_values_clause().join(", ")
+        ),
+        MysqlInsertStruct::generate_insert_sql()
+    );
+    let value = MysqlInsertStruct {
+        title: String::new(),
+        start_time: String::new(),
+        ty: 2,
+    };
+    assert_eq!(2, value.insert_clauses());
+}
+
+#[cfg(test)]
+#[derive(GenInsert)]
+#[config(table = "advert", database = "postgres", conflict = ["id"])]
+pub struct PgUpsertStruct {
+    pub id: i32,
+    #[value(conflict_update)]
+    pub title: String,
+    #[field(rename = "type")]
+    #[value(conflict_update)]
+    pub ty: i32,
+    pub created_at: String,
+}
+
+#[cfg(test)]
+#[derive(GenInsert)]
+#[config(table = "advert_tag", database = "sqlite", conflict = ["advert_id", "tag"])]
+pub struct SqliteUpsertStruct {
+    pub advert_id: i32,
+    pub tag: String,
+}
+
+#[test]
+fn upsert_test() {
+    assert_eq!(
+        Ok(
+            "INSERT INTO advert (id, title, type, created_at) VALUES ($1, $2, $3, $4) \
+            ON CONFLICT (id) DO UPDATE SET title = EXCLUDED.title, type = EXCLUDED.type"
+                .to_string()
+        ),
+        PgUpsertStruct::generate_upsert_sql()
+    );
+    assert_eq!(
+        Ok(
+            "INSERT INTO advert (id, title, type, created_at) VALUES (?, ?, ?, ?) \
+            ON DUPLICATE KEY UPDATE title = VALUES(title), type = VALUES(type)"
+                .to_string()
+        ),
+        PgUpsertStruct::generate_upsert_sql_for(Dialect::MySql)
+    );
+    assert_eq!(
+        Ok("MERGE INTO advert AS target \
+            USING (VALUES (@p1, @p2, @p3, @p4)) AS source (id, title, type, created_at) \
+            ON target.id = source.id \
+            WHEN MATCHED THEN UPDATE SET title = source.title, type = source.type \
+            WHEN NOT MATCHED THEN INSERT (id, title, type, created_at) \
+            VALUES (source.id, source.title, source.type, source.created_at);"
+            .to_string()),
+        PgUpsertStruct::generate_upsert_sql_for(Dialect::MsSql)
+    );
+
+    // 没有需要更新的字段时忽略冲突的行
+    assert_eq!(
+        Ok("INSERT INTO advert_tag (advert_id, tag) VALUES (?, ?) \
+            ON CONFLICT (advert_id, tag) DO NOTHING"
+            .to_string()),
+        SqliteUpsertStruct::generate_upsert_sql()
+    );
+    assert_eq!(
+        Ok("INSERT INTO advert_tag (advert_id, tag) VALUES (?, ?) \
+            ON DUPLICATE KEY UPDATE advert_id = advert_id"
+            .to_string()),
+        SqliteUpsertStruct::generate_upsert_sql_for(Dialect::MariaDb)
+    );
+    assert_eq!(
+        Ok("MERGE INTO advert_tag AS target \
+            USING (VALUES (@p1, @p2)) AS source (advert_id, tag) \
+            ON target.advert_id = source.advert_id AND target.tag = source.tag \
+            WHEN NOT MATCHED THEN INSERT (advert_id, tag) \
+            VALUES (source.advert_id, source.tag);"
+            .to_string()),
+        SqliteUpsertStruct::generate_upsert_sql_for(Dialect::MsSql)
+    );
+
+    // 没有设置 `conflict` 时返回错误
+    assert_eq!(
+        Err(SqlToolError::MissingConflict),
+        PgInsertStruct::generate_upsert_sql()
+    );
+}
+
+#[derive(GenSet)]
+#[config(table = "advert", database = "postgres", ignore_no_macro_set = false)]
+pub struct PgUpdateStruct {
+    #[set(r#where)]
+    pub id: Option<i32>,
+    pub title: Option<String>,
+    #[set(value = "now()")]
+    pub updated_at: (),
+}
+
+#[derive(GenSet)]
+#[config(
+    table = "advert",
+    database = "mysql",
+    ignore_no_macro_set = false,
+    allow_full_update = true
+)]
+pub struct MysqlUpdateAllStruct {
+    pub title: Option<String>,
+    pub sort: Option<i32>,
+}
+
+#[test]
+fn update_test() {
+    let value = PgUpdateStruct {
+        id: Some(1),
+        title: Some("这是标题".to_string()),
+        updated_at: (),
+    };
+    assert_eq!(
+        Ok("UPDATE advert SET title = $1, updated_at = now() WHERE id = $2".to_string()),
+        value.generate_update_sql()
+    );
+    let value = PgUpdateStruct {
+        id: None,
+        title: Some("这是标题".to_string()),
+        updated_at: (),
+    };
+    assert_eq!(Err(SqlToolError::MissingWhere), value.generate_update_sql());
+    let value = PgSetStruct {
+        id: 1,
+        title: None,
+        subtitle: None,
+        image_url: None,
+        link_url: None,
+        description: None,
+        updated_at: None,
+    };
+    assert_eq!(Err(SqlToolError::MissingTable), value.generate_update_sql());
+
+    let value = MysqlUpdateAllStruct {
+        title: None,
+        sort: Some(1),
+    };
+    assert_eq!(
+        Ok("UPDATE advert SET sort = ?".to_string()),
+        value.generate_update_sql()
+    );
+    let value = MysqlUpdateAllStruct {
+        title: None,
+        sort: None,
+    };
+    assert_eq!(Err(SqlToolError::EmptySet), value.generate_update_sql());
+}
+
+#[derive(GenSet, GenInsert, GenFields, GenSelect, GenValues)]
+#[config(table = "advert", database = "postgres")]
+pub struct BorrowedUpdateStruct<'a> {
+    #[set(r#where)]
+    pub id: i32,
+    #[set()]
+    pub title: Option<String>,
+    #[field(ignore)]
+    #[select(ignore)]
+    #[value(ignore)]
+    pub operator: &'a str,
+}
+
+#[test]
+fn generic_struct_test() {
+    // 带有生命周期参数的结构体同样可以派生，借用的字段不参与生成
+    let operator = "admin".to_string();
+    let value = BorrowedUpdateStruct {
+        id: 1,
+        title: Some("这是标题".to_string()),
+        operator: &operator,
+    };
+    assert_eq!(
+        Ok("UPDATE advert SET title = $1 WHERE id = $2".to_string()),
+        value.generate_update_sql()
+    );
+    assert_eq!(
+        "INSERT INTO advert (id, title) VALUES ($1, $2)",
+        BorrowedUpdateStruct::generate_insert_sql()
+    );
+    assert_eq!(
+        vec!["id", "title"],
+        BorrowedUpdateStruct::generate_select_clause()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenDelete)]
+#[config(table = "advert", database = "postgres")]
+pub struct PgDeleteStruct {
+    #[r#where()]
+    pub id: Option<i32>,
+    #[r#where()]
+    pub title: Option<String>,
+    #[r#where(condition = "<=")]
+    pub end_time: Option<String>,
+}
+
+#[cfg(test)]
+#[derive(GenDelete)]
+#[config(table = "advert", database = "sqlite", allow_full_delete = true)]
+pub struct SqliteDeleteAllStruct {
+    #[r#where()]
+    pub id: Option<i32>,
+}
+
+#[test]
+fn delete_test() {
+    let value = PgDeleteStruct {
+        id: Some(1),
+        title: None,
+        end_time: Some("2024/12/12".to_string()),
+    };
+    assert_eq!(
+        Ok("DELETE FROM advert WHERE id = $1 AND end_time <= $2".to_string()),
+        value.generate_delete_sql()
+    );
+    let (_, args) = value.generate_delete_sql_with_args().unwrap();
+    assert_eq!(args.len(), 2);
+    assert_eq!(args[0].downcast_ref::<Option<i32>>(), Some(&Some(1)));
+
+    let value = PgDeleteStruct {
+        id: None,
+        title: None,
+        end_time: None,
+    };
+    assert_eq!(Err(SqlToolError::MissingWhere), value.generate_delete_sql());
+
+    let value = SqliteDeleteAllStruct { id: None };
+    assert_eq!(
+        Ok("DELETE FROM advert".to_string()),
+        value.generate_delete_sql()
+    );
+    let value = SqliteDeleteAllStruct { id: Some(1) };
+    assert_eq!(
+        Ok("DELETE FROM advert WHERE id = ?".to_string()),
+        value.generate_delete_sql()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenSelect)]
+#[config(table = "advert")]
+pub struct SelectTableStruct {
+    pub title: String,
+    #[select(rename = "type")]
+    pub ty: i32,
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct SelectFilterStruct {
+    #[r#where()]
+    pub title: Option<String>,
+}
+
+#[test]
+fn select_builder_test() {
+    let value = PgWhereStruct {
+        keyword: None,
+        start_time: Some("2024/12/12".to_string()),
+        end_time: None,
+        ty: 1,
+        page_info: 0,
+    };
+    let (sql, args) = SelectBuilder::<SelectTableStruct>::new()
+        .filter(&value)
+        .build_with_args()
+        .unwrap();
+    assert_eq!(
+        "SELECT title, type FROM advert WHERE start_time >= $1 AND ty = $2",
+        sql
+    );
+    assert_eq!(args.len(), 2);
+
+    // 没有生效的条件时，不会生成 WHERE
+    let value = SelectFilterStruct { title: None };
+    assert_eq!(
+        Ok("SELECT title, type FROM advert".to_string()),
+        SelectBuilder::<SelectTableStruct>::new()
+            .filter(&value)
+            .build()
+    );
+    assert_eq!(
+        Ok("SELECT title, type FROM advert".to_string()),
+        SelectBuilder::<SelectTableStruct>::new().build()
+    );
+    assert_eq!(Some("advert"), SelectTableStruct::table_name());
+    assert_eq!(
+        Err(SqlToolError::MissingTable),
+        SelectBuilder::<SelectStruct>::new().build()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenWhere, GenValues)]
+#[config(database = "mssql")]
+pub struct MssqlWhereStruct {
+    #[r#where(condition_all = "({name} = {index} OR {index} IS NULL)")]
+    #[value(index = 3)]
+    pub title: Option<String>,
+    #[r#where()]
+    pub ty: i32,
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "mariadb")]
+pub struct MariadbWhereStruct {
+    #[r#where(condition_all = "({name} = {index} OR {index} IS NULL)")]
+    pub title: Option<String>,
+}
+
+#[test]
+fn dialect_test() {
+    assert_eq!(Ok(Dialect::MsSql), "mssql".parse::<Dialect>());
+    assert!("oracle".parse::<Dialect>().is_err());
+    assert_eq!(
+        "\"my\"\"table\"",
+        Dialect::Sqlite.quote_identifier("my\"table")
+    );
+    assert_eq!("[order]", Dialect::MsSql.quote_identifier("order"));
+    assert!(Dialect::Postgres.numbered_placeholders());
+    assert!(!Dialect::MariaDb.numbered_placeholders());
+    assert_eq!(2098, Dialect::MsSql.max_parameters());
+
+    let value = MssqlWhereStruct {
+        title: Some("title".to_string()),
+        ty: 1,
+    };
+    let (fields, args) = value.generate_where_clause_with_args();
+    assert_eq!(vec!["(title = @p1 OR @p1 IS NULL)", "ty = @p2"], fields);
+    assert_eq!(args.len(), 2);
+    assert_eq!(
+        vec!["@p3", "@p1"],
+        MssqlWhereStruct::generate_values_clause()
+    );
+
+    // 使用 `?` 的数据库需要为每个占位符绑定一次参数
+    let value = MariadbWhereStruct {
+        title: Some("title".to_string()),
+    };
+    let (fields, args) = value.generate_where_clause_with_args();
+    assert_eq!(vec!["(title = ? OR ? IS NULL)"], fields);
+    assert_eq!(args.len(), 2);
+}
+
+#[test]
+fn dialect_for_test() {
+    let value = PgWhereStruct {
+        keyword: Some("keyword".to_string()),
+        start_time: None,
+        end_time: None,
+        ty: 1,
+        page_info: 0,
+    };
+    assert_eq!(
+        vec!["title like ?", "ty = ?"],
+        value.generate_where_clause_for(Dialect::Sqlite)
+    );
+    assert_eq!(
+        vec!["title like @p3", "ty = @p4"],
+        value.generate_where_clause_with_index_for(3, Dialect::MsSql)
+    );
+    let (fields, args) = value.generate_where_clause_with_args_for(Dialect::MySql);
+    assert_eq!(vec!["title like ?", "ty = ?"], fields);
+    assert_eq!(args.len(), 2);
+
+    // 同一个结构体在不同的数据库上绑定参数的数量不同
+    let value = MariadbWhereStruct {
+        title: Some("title".to_string()),
+    };
+    let (fields, args) = value.generate_where_clause_with_args_for(Dialect::Postgres);
+    assert_eq!(vec!["(title = $1 OR $1 IS NULL)"], fields);
+    assert_eq!(args.len(), 1);
+
+    assert_eq!(
+        vec!["@p3", "@p1"],
+        MssqlWhereStruct::generate_values_clause_for(Dialect::MsSql)
+    );
+    assert_eq!(
+        vec!["?", "?"],
+        MssqlWhereStruct::generate_values_clause_for(Dialect::Sqlite)
+    );
+    assert_eq!(
+        "INSERT INTO advert (title, subtitle, image_url, start_time, type) VALUES (@p1, @p1, @p2, now(), @p3)",
+        PgInsertStruct::generate_insert_sql_for(Dialect::MsSql)
+    );
+
+    let value = PgUpdateStruct {
+        id: Some(1),
+        title: Some("这是标题".to_string()),
+        updated_at: (),
+    };
+    assert_eq!(
+        Ok("UPDATE advert SET title = ?, updated_at = now() WHERE id = ?".to_string()),
+        value.generate_update_sql_for(Dialect::Sqlite)
+    );
+
+    let value = PgDeleteStruct {
+        id: Some(1),
+        title: None,
+        end_time: None,
+    };
+    assert_eq!(
+        Ok("DELETE FROM advert WHERE id = ?".to_string()),
+        value.generate_delete_sql_for(Dialect::Sqlite)
+    );
+
+    let value = SelectFilterStruct {
+        title: Some("title".to_string()),
+    };
+    assert_eq!(
+        Ok("SELECT title, type FROM advert WHERE title = ?".to_string()),
+        SelectBuilder::<SelectTableStruct>::new()
+            .filter(&value)
+            .dialect(Dialect::Sqlite)
+            .build()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenFields, GenSelect, GenInsert)]
+#[config(table = "advert", database = "mysql", quote_identifiers = true)]
+pub struct QuoteStruct {
+    pub title: String,
+    #[field(rename = "type")]
+    #[select(rename = "type")]
+    pub ty: i32,
+    #[field(ignore)]
+    #[select(rename = "count(*) AS total", quote = false)]
+    #[value(ignore)]
+    pub total: i64,
+}
+
+#[cfg(test)]
+#[derive(GenWhere, GenSet)]
+#[config(database = "postgres")]
+pub struct QuoteWhereStruct {
+    #[r#where(rename = "order", quote = true)]
+    #[set(r#where, rename = "order", quote = true)]
+    pub order: i32,
+    #[r#where(condition_all = "{name} like {index}", quote = true)]
+    #[set(rename = "a\"b", quote = true)]
+    pub title: String,
+    #[r#where()]
+    #[set()]
+    pub ty: i32,
+}
+
+#[cfg(test)]
+#[derive(GenSelect, GenWhere)]
+#[config(table = "users", database = "postgres", quote_identifiers = true)]
+pub struct QuoteExpressionStruct {
+    #[select(rename = "order")]
+    #[r#where(rename = "order")]
+    pub order: i32,
+    #[r#where(rename = "lower(name)")]
+    pub name: String,
+    #[select(rename = "u.id")]
+    #[r#where(rename = "u.id")]
+    pub id: i32,
+    #[select(rename = "count(*) AS total")]
+    pub total: i64,
+}
+
+#[test]
+fn quote_identifiers_test() {
+    assert_eq!(
+        vec!["`title`", "`type`"],
+        QuoteStruct::generate_fields_clause()
+    );
+    assert_eq!(
+        vec!["\"title\"", "\"type\"", "count(*) AS total"],
+        QuoteStruct::generate_select_clause_for(Dialect::Postgres)
+    );
+    assert_eq!(
+        "INSERT INTO advert (`title`, `type`) VALUES (?, ?)",
+        QuoteStruct::generate_insert_sql()
+    );
+    assert_eq!(
+        "INSERT INTO advert ([title], [type]) VALUES (@p1, @p2)",
+        QuoteStruct::generate_insert_sql_for(Dialect::MsSql)
+    );
+    assert_eq!(
+        Ok("SELECT `title`, `type`, count(*) AS total FROM advert".to_string()),
+        SelectBuilder::<QuoteStruct>::new().build()
+    );
+
+    let value = QuoteWhereStruct {
+        order: 1,
+        title: "title".to_string(),
+        ty: 1,
+    };
+    assert_eq!(
+        vec!["\"order\" = $1", "\"title\" like $2", "ty = $3"],
+        value.generate_where_clause()
+    );
+    assert_eq!(
+        vec!["[order] = @p1", "[title] like @p2", "ty = @p3"],
+        value.generate_where_clause_for(Dialect::MsSql)
+    );
+    assert_eq!(
+        (
+            vec!["\"a\"\"b\" = $1".to_string(), "ty = $2".to_string()],
+            vec!["\"order\" = $3".to_string()]
+        ),
+        value.generate_set_and_where_clause()
+    );
+
+    // `quote_identifiers` 不会包裹表达式以及带有表名的名称
+    let value = QuoteExpressionStruct {
+        order: 1,
+        name: "name".to_string(),
+        id: 1,
+        total: 0,
+    };
+    assert_eq!(
+        vec!["\"order\"", "\"name\"", "u.id", "count(*) AS total"],
+        QuoteExpressionStruct::generate_select_clause()
+    );
+    assert_eq!(
+        vec!["\"order\" = $1", "lower(name) = $2", "u.id = $3"],
+        value.generate_where_clause()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenSelect, GenWhere)]
+#[config(table = "users", database = "postgres", alias = "u")]
+pub struct AliasStruct {
+    #[r#where()]
+    pub id: Option<i32>,
+    #[r#where(condition_all = "lower({name}) like {index}")]
+    pub name: Option<String>,
+    #[select(rename = "f.follower_id")]
+    #[r#where(rename = "f.follower_id")]
+    pub follower_id: Option<i32>,
+    #[select(rename = "count(*) AS total")]
+    pub total: i64,
+}
+
+#[test]
+fn alias_test() {
+    assert_eq!(
+        vec!["u.id", "u.name", "f.follower_id", "count(*) AS total"],
+        AliasStruct::generate_select_clause()
+    );
+    assert_eq!(
+        vec!["a.id", "a.name", "f.follower_id", "count(*) AS total"],
+        AliasStruct::generate_select_clause_with_alias("a")
+    );
+    assert_eq!(Some("u"), AliasStruct::table_alias());
+    assert_eq!(
+        vec!["title", "type"],
+        SelectTableStruct::generate_select_clause()
+    );
+    assert_eq!(
+        vec!["a.title", "a.type"],
+        SelectTableStruct::generate_select_clause_with_alias("a")
+    );
+    assert_eq!(
+        vec!["a.`title`", "a.`type`", "count(*) AS total"],
+        QuoteStruct::generate_select_clause_with_alias("a")
+    );
+
+    let value = AliasStruct {
+        id: Some(1),
+        name: Some("%name%".to_string()),
+        follower_id: Some(2),
+        total: 0,
+    };
+    assert_eq!(
+        vec!["u.id = $1", "lower(u.name) like $2", "f.follower_id = $3"],
+        value.generate_where_clause()
+    );
+    assert_eq!(
+        vec!["a.id = $1", "lower(a.name) like $2", "f.follower_id = $3"],
+        value.generate_where_clause_with_alias("a")
+    );
+    // 引号包裹的字段名同样会加上表别名
+    let value = QuoteWhereStruct {
+        order: 1,
+        title: "title".to_string(),
+        ty: 1,
+    };
+    assert_eq!(
+        vec!["q.\"order\" = $1", "q.\"title\" like $2", "q.ty = $3"],
+        value.generate_where_clause_with_alias("q")
+    );
+    assert_eq!(
+        Ok("SELECT u.id, u.name, f.follower_id, count(*) AS total FROM users u WHERE u.id = $1 AND lower(u.name) like $2 AND f.follower_id = $3".to_string()),
+        SelectBuilder::<AliasStruct>::new()
+            .filter(&AliasStruct {
+                id: Some(1),
+                name: Some("%name%".to_string()),
+                follower_id: Some(2),
+                total: 0,
+            })
+            .build()
+    );
+}
+
+#[test]
+fn next_param_index_test() {
+    assert_eq!(3, PgValuesStruct::next_param_index());
+    assert_eq!(7, PgValuesStructIndex::next_param_index());
+    assert_eq!(5, MssqlValuesStructIndex::next_param_index());
+
+    // SET 和 WHERE 来自不同的结构体时，WHERE 从 SET 之后的序号开始
+    let set = PgUpdateStruct {
+        id: None,
+        title: Some("这是标题".to_string()),
+        updated_at: (),
+    };
+    let filter = PgWhereStruct {
+        keyword: None,
+        start_time: Some("2024/12/12".to_string()),
+        end_time: None,
+        ty: 1,
+        page_info: 0,
+    };
+    let (set_data, _) = set.generate_set_and_where_clause();
+    assert_eq!(vec!["title = $1", "updated_at = now()"], set_data);
+    assert_eq!(2, set.next_param_index());
+    assert_eq!(
+        vec!["start_time >= $2", "ty = $3"],
+        filter.generate_where_clause_with_index(set.next_param_index())
+    );
+    assert_eq!(3, filter.next_param_index());
+    assert_eq!(4, filter.next_param_index_with_index(2));
+
+    // 被 ignore_none 过滤掉的字段不会占用序号
+    let set = PgUpdateStruct {
+        id: Some(1),
+        title: None,
+        updated_at: (),
+    };
+    assert_eq!(2, set.next_param_index());
+    let filter = MariadbWhereStruct { title: None };
+    assert_eq!(1, filter.next_param_index());
+}
+
+#[test]
+fn param_counter_test() {
+    let set = PgUpdateStruct {
+        id: Some(1),
+        title: Some("这是标题".to_string()),
+        updated_at: (),
+    };
+    let filter = PgWhereStruct {
+        keyword: Some("keyword".to_string()),
+        start_time: None,
+        end_time: None,
+        ty: 1,
+        page_info: 0,
+    };
+
+    let mut counter = ParamCounter::new(Dialect::Postgres);
+    assert_eq!(
+        vec!["title = $1", "updated_at = now()"],
+        set.generate_set_clause_with_counter(&mut counter)
+    );
+    assert_eq!(
+        vec!["title like $2", "ty = $3"],
+        filter.generate_where_clause_with_counter(&mut counter)
+    );
+    // 设置了 `index` 的字段使用固定的序号
+    assert_eq!(
+        vec!["$4", "$5", "$2", "'这是描述'"],
+        MysqlValuesStruct::generate_values_clause_with_counter(&mut counter)
+    );
+    assert_eq!(6, counter.next_index());
+    assert_eq!(5, counter.bind_count());
+
+    // 使用 `?` 的数据库按照占位符出现的次数计算需要绑定的参数
+    let mut counter = ParamCounter::new(Dialect::MySql);
+    let value = MariadbWhereStruct {
+        title: Some("title".to_string()),
+    };
+    let (set_data, where_data) = set.generate_set_and_where_clause_with_counter(&mut counter);
+    assert_eq!(vec!["title = ?", "updated_at = now()"], set_data);
+    assert_eq!(vec!["id = ?"], where_data);
+    assert_eq!(
+        vec!["(title = ? OR ? IS NULL)"],
+        value.generate_where_clause_with_counter(&mut counter)
+    );
+    assert_eq!(4, counter.bind_count());
+
+    let mut counter = ParamCounter::with_start(Dialect::MsSql, 3);
+    assert_eq!(
+        vec!["ty = @p3"],
+        MssqlWhereStruct { title: None, ty: 1 }.generate_where_clause_with_counter(&mut counter)
+    );
+    assert_eq!(4, counter.next_index());
+    assert_eq!(1, counter.bind_count());
+}
+
+#[test]
+fn fragment_test() {
+    let filter = PgWhereStructCondition {
+        keyword: Some("keyword".to_string()),
+        start_time: Some("2024/12/12".to_string()),
+        end_time: None,
+        ty: 1,
+        page_info: 0,
+    };
+    let fragments = filter.generate_where_fragments();
+    assert_eq!(
+        "keyword like $1 AND start_time >= '2024/12/12' AND end_time <= $2",
+        fragments.join(" AND ")
+    );
+    let metadata: Vec<_> = fragments
+        .iter()
+        .map(|fragment| (fragment.field(), fragment.index(), fragment.is_expression()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("keyword", Some(1), true),
+            ("start_time", None, true),
+            ("end_time", Some(2), false),
+        ],
+        metadata
+    );
+    assert_eq!(2, fragments.bind_count());
+    assert_eq!(filter.generate_where_clause(), fragments.into_strings());
+
+    // 设置了 `index` 的字段记录固定的序号，`value` 不绑定参数
+    let values = MysqlValuesStruct::generate_values_fragments();
+    assert_eq!("?, ?, ?, '这是描述'", values.join(", "));
+    let indexes: Vec<_> = values.iter().map(SqlFragment::index).collect();
+    assert_eq!(vec![Some(1), Some(2), Some(2), None], indexes);
+    assert_eq!("description", values.fragments()[3].field());
+
+    let fields = QuoteStruct::generate_fields_fragments();
+    assert_eq!(
+        QuoteStruct::generate_fields_clause(),
+        fields.iter().map(ToString::to_string).collect::<Vec<_>>()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct PgWhereIndexStruct {
+    #[r#where(index = 2)]
+    pub id: i32,
+    #[r#where()]
+    pub ty: i32,
+}
+
+#[test]
+fn placeholder_map_test() {
+    // `email` 通过 `index` 引用 `phone` 的 `$2`，不属于任何占位符
+    assert_eq!(
+        &[(1, "id"), (2, "phone")],
+        PgValuesStruct::placeholder_map()
+    );
+    assert_eq!(
+        &[
+            (2, "email"),
+            (3, "id"),
+            (4, "phone"),
+            (5, "user_type"),
+            (6, "identifier")
+        ],
+        PgValuesStructIndex::placeholder_map()
+    );
+
+    let value = PgSetStruct {
+        id: 1,
+        title: None,
+        subtitle: None,
+        image_url: None,
+        link_url: None,
+        description: None,
+        updated_at: None,
+    };
+    assert_eq!(
+        &[(1, "id"), (2, "image_url"), (3, "description")],
+        value.placeholder_map()
+    );
+
+    // 参数按照占位符的序号排列，而不是字段的顺序
+    let value = PgWhereIndexStruct { id: 7, ty: 1 };
+    assert_eq!(&[(1, "ty"), (2, "id")], value.placeholder_map());
+    let (where_data, args) = value.generate_where_clause_with_args();
+    assert_eq!(vec!["id = $2", "ty = $1"], where_data);
+    assert_eq!(args[0].downcast_ref::<i32>(), Some(&1));
+    assert_eq!(args[1].downcast_ref::<i32>(), Some(&7));
+}
+
+#[cfg(test)]
+#[derive(GenWhere, GenSet)]
+#[config(database = "postgres")]
+pub struct PgRenumberStruct {
+    #[set()]
+    #[r#where()]
+    pub a: Option<i32>,
+    #[set(index = 1)]
+    #[r#where(index = 1)]
+    pub b: i32,
+    #[set(r#where)]
+    #[r#where()]
+    pub c: i32,
+}
+
+#[test]
+fn renumber_test() {
+    // `a` 为 `None` 时 `b` 使用 `a` 的位置，`c` 的序号前移，不会留下空缺
+    let value = PgRenumberStruct {
+        a: None,
+        b: 5,
+        c: 6,
+    };
+    let (where_data, args) = value.generate_where_clause_with_args();
+    assert_eq!(vec!["b = $1", "c = $2"], where_data);
+    assert_eq!(args[0].downcast_ref::<i32>(), Some(&5));
+    assert_eq!(args[1].downcast_ref::<i32>(), Some(&6));
+    assert_eq!(3, WhereAttributeMacro::next_param_index(&value));
+    assert_eq!(
+        &[(1, "a"), (2, "c")],
+        WhereAttributeMacro::placeholder_map(&value)
+    );
+
+    // `a` 存在时 `b` 引用 `a` 的占位符，只绑定 `a`
+    let value = PgRenumberStruct {
+        a: Some(4),
+        b: 5,
+        c: 6,
+    };
+    let (where_data, args) = value.generate_where_clause_with_args();
+    assert_eq!(vec!["a = $1", "b = $1", "c = $2"], where_data);
+    assert_eq!(2, args.len());
+    assert_eq!(args[0].downcast_ref::<Option<i32>>(), Some(&Some(4)));
+    assert_eq!(
+        vec!["a = $2", "b = $2", "c = $3"],
+        value.generate_where_clause_with_index(2)
+    );
+
+    // 重新编号同样作用于 `SET` 和 `WHERE` 部分
+    let value = PgRenumberStruct {
+        a: None,
+        b: 5,
+        c: 6,
+    };
+    assert_eq!(
+        (vec!["b = $1".to_string()], vec!["c = $2".to_string()]),
+        value.generate_set_and_where_clause()
+    );
+    let mut counter = ParamCounter::new(Dialect::Postgres);
+    assert_eq!(
+        vec!["b = $1"],
+        value.generate_set_clause_with_counter(&mut counter)
+    );
+    assert_eq!(2, counter.next_index());
+
+    // 序号的空缺在运行时压缩，`description` 的 `index = 4` 排在 `url` 之后
+    let value = PgSetStruct {
+        id: 1,
+        title: None,
+        subtitle: None,
+        image_url: None,
+        link_url: None,
+        description: Some("这是描述".to_string()),
+        updated_at: None,
+    };
+    assert_eq!(
+        vec!["id = $1", "description = $2"],
+        value.generate_set_clause()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenValues)]
+#[config(database = "mssql")]
+pub struct MssqlValuesColumn {
+    pub id: i32,
+}
+
+#[test]
+fn values_rows_test() {
+    // 每行占用 `$1`、`$2` 两个序号，`email` 引用的序号随行后移
+    assert_eq!(
+        vec!["($1, $2, $2, '这是描述'), ($3, $4, $4, '这是描述')"],
+        PgValuesStruct::generate_values_rows(2)
+    );
+    // 小于起始索引的 `$2` 引用的是语句中其他部分的参数，不会后移
+    assert_eq!(
+        vec!["($3, $4, $2, '这是描述', $5::bit(4), $6), ($7, $8, $2, '这是描述', $9::bit(4), $10)"],
+        PgValuesStructIndex::generate_values_rows(2)
+    );
+    assert_eq!(
+        vec!["(?, ?, ?, '这是描述'), (?, ?, ?, '这是描述'), (?, ?, ?, '这是描述')"],
+        MysqlValuesStruct::generate_values_rows(3)
+    );
+    assert_eq!(
+        vec!["(?, ?, ?, '这是描述')"],
+        PgValuesStruct::generate_values_rows_for(1, Dialect::Sqlite)
+    );
+    assert!(PgValuesStruct::generate_values_rows(0).is_empty());
+
+    // mssql 最多 2098 个参数，`@p1`、`@p2` 已被占用，每行 2 个参数，同时单条语句最多 1000 行
+    assert_eq!(
+        1000,
+        MssqlValuesStructIndex::rows_per_statement(Dialect::MsSql)
+    );
+    let statements = MssqlValuesStructIndex::generate_values_rows(1050);
+    assert_eq!(2, statements.len());
+    assert!(statements[0].ends_with("(@p2001, @p2002, @p2, '这是描述')"));
+    assert!(statements[1].starts_with("(@p3, @p4, @p2, '这是描述'), "));
+
+    // 每行只有 1 个参数时受行数的限制
+    assert_eq!(1000, MssqlValuesColumn::rows_per_statement(Dialect::MsSql));
+    let statements = MssqlValuesColumn::generate_values_rows(1500);
+    assert_eq!(2, statements.len());
+    assert!(statements[0].ends_with("(@p1000)"));
+    assert!(statements[1].ends_with("(@p500)"));
+
+    // 使用 `?` 的数据库按照占位符出现的次数计算
+    assert_eq!(
+        Dialect::MySql.max_parameters() / 3,
+        MysqlValuesStruct::rows_per_statement(Dialect::MySql)
+    );
+}
+
+#[test]
+fn returning_test() {
+    assert_eq!(
+        Ok("RETURNING id, user_name, email, description".to_string()),
+        FieldsStruct::generate_returning_clause(Dialect::Postgres, StatementKind::Update)
+    );
+    assert_eq!(
+        Ok("RETURNING id, user_name, email, description".to_string()),
+        SelectStruct::generate_select_returning_clause(Dialect::MariaDb, StatementKind::Insert)
+    );
+    assert_eq!(
+        Ok(
+            "OUTPUT INSERTED.id, INSERTED.user_name, INSERTED.email, INSERTED.description"
+                .to_string()
+        ),
+        FieldsStruct::generate_returning_clause(Dialect::MsSql, StatementKind::Insert)
+    );
+    assert_eq!(
+        Ok("OUTPUT DELETED.id, DELETED.user_name, DELETED.email, DELETED.description".to_string()),
+        FieldsStruct::generate_returning_clause(Dialect::MsSql, StatementKind::Delete)
+    );
+
+    // 使用数据库的引号，表达式不会加上 `INSERTED.`
+    assert_eq!(
+        Ok("RETURNING \"title\", \"type\", count(*) AS total".to_string()),
+        QuoteStruct::generate_select_returning_clause(Dialect::Sqlite, StatementKind::Delete)
+    );
+    assert_eq!(
+        Ok("OUTPUT INSERTED.[title], INSERTED.[type], count(*) AS total".to_string()),
+        QuoteStruct::generate_select_returning_clause(Dialect::MsSql, StatementKind::Update)
+    );
+
+    assert_eq!(
+        Err(SqlToolError::UnsupportedReturning(
+            Dialect::MySql,
+            StatementKind::Insert
+        )),
+        FieldsStruct::generate_returning_clause(Dialect::MySql, StatementKind::Insert)
+    );
+    assert_eq!(
+        Err(SqlToolError::UnsupportedReturning(
+            Dialect::MariaDb,
+            StatementKind::Update
+        )),
+        FieldsStruct::generate_returning_clause(Dialect::MariaDb, StatementKind::Update)
+    );
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct GroupWhereStruct {
+    #[r#where(group = "keyword", join = "OR", condition = "LIKE")]
+    pub title: Option<String>,
+    #[r#where()]
+    pub ty: i32,
+    #[r#where(group = "keyword", condition = "LIKE")]
+    pub subtitle: Option<String>,
+    #[r#where(not)]
+    pub status: Option<i32>,
+}
+
+#[cfg(test)]
+#[derive(GenWhere, GenDelete)]
+#[config(table = "advert", database = "mysql", join = "or")]
+pub struct OrWhereStruct {
+    #[r#where()]
+    pub id: Option<i32>,
+    #[r#where(group = "range", join = "AND", condition = ">=")]
+    pub start: Option<i32>,
+    #[r#where(group = "range", condition = "<=", rename = "start")]
+    pub end: Option<i32>,
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct ExpressionGroupStruct {
+    #[r#where(group = "filter", condition_all = "a = 1 OR b = {index}")]
+    pub b: i32,
+    #[r#where(group = "filter")]
+    pub c: i32,
+}
+
+#[test]
+fn where_group_test() {
+    // 分组的字段移动到分组第一个字段的位置，占位符按照移动后的顺序生成
+    let value = GroupWhereStruct {
+        title: Some("%标题%".to_string()),
+        ty: 1,
+        subtitle: Some("%副标题%".to_string()),
+        status: Some(0),
+    };
+    assert_eq!(
+        vec![
+            "(title LIKE $1 OR subtitle LIKE $2)",
+            "ty = $3",
+            "NOT (status = $4)"
+        ],
+        value.generate_where_clause()
+    );
+    let (where_data, args) = value.generate_where_clause_with_args_for(Dialect::MySql);
+    assert_eq!(
+        vec![
+            "(title LIKE ? OR subtitle LIKE ?)",
+            "ty = ?",
+            "NOT (status = ?)"
+        ],
+        where_data
+    );
+    assert_eq!(
+        args[1].downcast_ref::<Option<String>>(),
+        Some(&Some("%副标题%".to_string()))
+    );
+    assert_eq!(args[2].downcast_ref::<i32>(), Some(&1));
+    let fragments = value.generate_where_fragments();
+    assert_eq!(fragments.fragments()[0].field(), "keyword");
+    assert!(fragments.fragments()[0].is_expression());
+
+    // 只有一个字段生效时不需要括号，没有字段生效时跳过整个分组
+    let value = GroupWhereStruct {
+        title: None,
+        ty: 1,
+        subtitle: Some("%副标题%".to_string()),
+        status: None,
+    };
+    assert_eq!(
+        vec!["subtitle LIKE $1", "ty = $2"],
+        value.generate_where_clause()
+    );
+    let value = GroupWhereStruct {
+        title: None,
+        ty: 1,
+        subtitle: None,
+        status: None,
+    };
+    assert_eq!(vec!["ty = $1"], value.generate_where_clause());
+
+    // `#[config(join = "OR")]` 时所有条件合并为一个片段
+    let value = OrWhereStruct {
+        id: Some(1),
+        start: Some(10),
+        end: Some(20),
+    };
+    assert_eq!(
+        vec!["(id = ? OR (start >= ? AND start <= ?))"],
+        value.generate_where_clause()
+    );
+    assert_eq!(
+        Ok("DELETE FROM advert WHERE (id = ? OR (start >= ? AND start <= ?))".to_string()),
+        value.generate_delete_sql()
+    );
+    let value = OrWhereStruct {
+        id: None,
+        start: Some(10),
+        end: None,
+    };
+    assert_eq!(vec!["start >= ?"], value.generate_where_clause());
+
+    // 分组中的 `condition_all` 可能包含 `OR`，合并时使用括号包裹
+    let value = ExpressionGroupStruct { b: 2, c: 3 };
+    assert_eq!(
+        vec!["((a = 1 OR b = $1) AND c = $2)"],
+        value.generate_where_clause()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct InWhereStruct {
+    #[r#where(in)]
+    pub ids: Vec<i64>,
+    #[r#where()]
+    pub ty: i32,
+    #[r#where(in, not, empty = "skip", rename = "status")]
+    pub exclude_status: Option<Vec<i32>>,
+}
+
+#[cfg(test)]
+#[derive(GenWhere, GenDelete)]
+#[config(table = "advert", database = "postgres")]
+pub struct SliceWhereStruct<'a> {
+    #[r#where(in)]
+    pub tags: &'a [String],
+    #[r#where(any)]
+    pub ids: Vec<i64>,
+}
+
+#[test]
+fn where_in_test() {
+    // 每个元素生成一个占位符，之后的字段从下一个序号开始
+    let value = InWhereStruct {
+        ids: vec![1, 2, 3],
+        ty: 1,
+        exclude_status: Some(vec![0, 9]),
+    };
+    assert_eq!(
+        vec!["ids IN ($1, $2, $3)", "ty = $4", "NOT (status IN ($5, $6))"],
+        value.generate_where_clause()
+    );
+    assert_eq!(7, value.next_param_index());
+    let (where_data, args) = value.generate_where_clause_with_args_for(Dialect::MySql);
+    assert_eq!(
+        vec!["ids IN (?, ?, ?)", "ty = ?", "NOT (status IN (?, ?))"],
+        where_data
+    );
+    assert_eq!(6, args.len());
+    assert_eq!(args[2].downcast_ref::<i64>(), Some(&3));
+    assert_eq!(args[3].downcast_ref::<i32>(), Some(&1));
+    assert_eq!(args[5].downcast_ref::<i32>(), Some(&9));
+
+    // 空列表默认生成恒为假的条件，`empty = "skip"` 时跳过，`None` 同样跳过
+    let value = InWhereStruct {
+        ids: vec![],
+        ty: 1,
+        exclude_status: Some(vec![]),
+    };
+    assert_eq!(vec!["1 = 0", "ty = $1"], value.generate_where_clause());
+    let value = InWhereStruct {
+        ids: vec![5],
+        ty: 1,
+        exclude_status: None,
+    };
+    assert_eq!(
+        vec!["ids IN ($1)", "ty = $2"],
+        value.generate_where_clause()
+    );
+
+    // 切片字段，以及 postgres 使用数组绑定的 `= ANY($n)`
+    let tags = vec!["rust".to_string(), "sql".to_string()];
+    let value = SliceWhereStruct {
+        tags: &tags,
+        ids: vec![1, 2],
+    };
+    assert_eq!(
+        vec!["tags IN ($1, $2)", "ids = ANY($3)"],
+        value.generate_where_clause()
+    );
+    let (sql, args) = value.generate_delete_sql_with_args().unwrap();
+    assert_eq!(
+        "DELETE FROM advert WHERE tags IN ($1, $2) AND ids = ANY($3)",
+        sql
+    );
+    assert_eq!(args[1].downcast_ref::<String>(), Some(&"sql".to_string()));
+    assert_eq!(args[2].downcast_ref::<Vec<i64>>(), Some(&vec![1, 2]));
+
+    // 运行时的数据库不是 postgres 时，`any` 与 `in` 相同逐个绑定元素
+    assert_eq!(
+        vec!["tags IN (@p1, @p2)", "ids IN (@p3, @p4)"],
+        value.generate_where_clause_for(Dialect::MsSql)
+    );
+    let (sql, args) = value
+        .generate_delete_sql_with_args_for(Dialect::MySql)
+        .unwrap();
+    assert_eq!(
+        "DELETE FROM advert WHERE tags IN (?, ?) AND ids IN (?, ?)",
+        sql
+    );
+    assert_eq!(4, args.len());
+    assert_eq!(args[2].downcast_ref::<i64>(), Some(&1));
+    assert_eq!(args[3].downcast_ref::<i64>(), Some(&2));
+    let value = SliceWhereStruct {
+        tags: &[],
+        ids: vec![],
+    };
+    assert_eq!(
+        vec!["1 = 0", "1 = 0"],
+        value.generate_where_clause_for(Dialect::Sqlite)
+    );
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct LikeWhereStruct {
+    #[r#where(like = "contains")]
+    pub title: String,
+    #[r#where(like = "prefix", case_insensitive, rename = "a.name")]
+    pub name: Option<String>,
+    #[r#where(like = "suffix", not)]
+    pub email: Option<String>,
+}
+
+#[test]
+fn where_like_test() {
+    let value = LikeWhereStruct {
+        title: "50%_off".to_string(),
+        name: Some("Tom".to_string()),
+        email: None,
+    };
+    // postgres 使用 `ILIKE`，其余数据库使用 `LOWER`
+    assert_eq!(
+        vec!["title LIKE $1 ESCAPE '!'", "a.name ILIKE $2 ESCAPE '!'"],
+        value.generate_where_clause()
+    );
+    let (where_data, args) = value.generate_where_clause_with_args_for(Dialect::MySql);
+    assert_eq!(
+        vec![
+            "title LIKE ? ESCAPE '!'",
+            "LOWER(a.name) LIKE LOWER(?) ESCAPE '!'"
+        ],
+        where_data
+    );
+    // 绑定的是转义并加上 `%` 之后的值
+    assert_eq!(
+        args[0].downcast_ref::<String>(),
+        Some(&"%50!%!_off%".to_string())
+    );
+    assert_eq!(
+        args[1].downcast_ref::<Option<String>>(),
+        Some(&Some("Tom%".to_string()))
+    );
+
+    let value = LikeWhereStruct {
+        title: "标题".to_string(),
+        name: None,
+        email: Some("@example.com".to_string()),
+    };
+    let (where_data, args) = value.generate_where_clause_with_args();
+    assert_eq!(
+        vec!["title LIKE $1 ESCAPE '!'", "NOT (email LIKE $2 ESCAPE '!')"],
+        where_data
+    );
+    assert_eq!(
+        args[1].downcast_ref::<Option<String>>(),
+        Some(&Some("%@example.com".to_string()))
+    );
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct RangeWhereStruct {
+    #[r#where(range)]
+    pub price: std::ops::Range<i32>,
+    #[r#where(range, rename = "created_at")]
+    pub created: (Option<String>, Option<String>),
+    #[r#where(range, not)]
+    pub id: Option<std::ops::RangeInclusive<i64>>,
+    #[r#where()]
+    pub ty: i32,
+}
+
+#[test]
+fn where_range_test() {
+    let value = RangeWhereStruct {
+        price: 10..20,
+        created: (
+            Some("2024-01-01".to_string()),
+            Some("2024-12-31".to_string()),
+        ),
+        id: Some(1..=100),
+        ty: 1,
+    };
+    assert_eq!(
+        vec![
+            "price >= $1 AND price < $2",
+            "created_at BETWEEN $3 AND $4",
+            "NOT (id BETWEEN $5 AND $6)",
+            "ty = $7"
+        ],
+        value.generate_where_clause()
+    );
+    assert_eq!(8, value.next_param_index());
+    let (_, args) = value.generate_where_clause_with_args();
+    assert_eq!(7, args.len());
+    assert_eq!(args[1].downcast_ref::<i32>(), Some(&20));
+    assert_eq!(
+        args[3].downcast_ref::<String>(),
+        Some(&"2024-12-31".to_string())
+    );
+    assert_eq!(args[5].downcast_ref::<i64>(), Some(&100));
+
+    // 只有一侧边界时生成单侧比较，两侧都没有时跳过该条件
+    let value = RangeWhereStruct {
+        price: 10..20,
+        created: (None, Some("2024-12-31".to_string())),
+        id: None,
+        ty: 1,
+    };
+    let (where_data, args) = value.generate_where_clause_with_args_for(Dialect::MySql);
+    assert_eq!(
+        vec!["price >= ? AND price < ?", "created_at <= ?", "ty = ?"],
+        where_data
+    );
+    assert_eq!(4, args.len());
+    let value = RangeWhereStruct {
+        price: 10..20,
+        created: (Some("2024-01-01".to_string()), None),
+        id: None,
+        ty: 1,
+    };
+    assert_eq!(
+        vec!["price >= $1 AND price < $2", "created_at >= $3", "ty = $4"],
+        value.generate_where_clause()
+    );
+}
+
+#[cfg(test)]
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct NullableWhereStruct {
+    #[r#where()]
+    pub deleted_at: Option<Option<String>>,
+    #[r#where(not_null, rename = "email")]
+    pub has_email: Option<Option<String>>,
+    #[r#where()]
+    pub ty: i32,
+}
+
+#[cfg(test)]
+#[derive(GenSet)]
+#[config(table = "users", database = "postgres", ignore_no_macro_set = false)]
+pub struct NullableSetStruct {
+    #[set(r#where)]
+    pub id: i32,
+    pub email: Option<Option<String>>,
+    pub name: Option<String>,
+}
+
+#[test]
+fn nullable_test() {
+    // `Some(None)` 生成 `IS NULL`，不会占用占位符，`None` 时跳过该字段
+    let value = NullableWhereStruct {
+        deleted_at: Some(None),
+        has_email: Some(None),
+        ty: 1,
+    };
+    assert_eq!(
+        vec!["deleted_at IS NULL", "email IS NOT NULL", "ty = $1"],
+        value.generate_where_clause()
+    );
+    let value = NullableWhereStruct {
+        deleted_at: Some(Some("2024-01-01".to_string())),
+        has_email: None,
+        ty: 1,
+    };
+    assert_eq!(
+        vec!["deleted_at = $1", "ty = $2"],
+        value.generate_where_clause()
+    );
+    let (_, args) = value.generate_where_clause_with_args();
+    assert_eq!(
+        args[0].downcast_ref::<String>(),
+        Some(&"2024-01-01".to_string())
+    );
+
+    // `SET` 中 `Some(None)` 生成 `col = NULL`
+    let value = NullableSetStruct {
+        id: 1,
+        email: Some(None),
+        name: Some("名称".to_string()),
+    };
+    assert_eq!(
+        Ok("UPDATE users SET email = NULL, name = $1 WHERE id = $2".to_string()),
+        value.generate_update_sql()
+    );
+    let value = NullableSetStruct {
+        id: 1,
+        email: Some(Some("a@example.com".to_string())),
+        name: None,
+    };
+    assert_eq!(
+        Ok("UPDATE users SET email = $1 WHERE id = $2".to_string()),
+        value.generate_update_sql()
+    );
+    assert_eq!(
+        vec![(1, "email"), (2, "name"), (3, "id")],
+        value.placeholder_map()
+    );
+}
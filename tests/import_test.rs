@@ -0,0 +1,90 @@
+//! 只导入派生宏和对应的 trait，生成的代码不依赖调用方导入的其他类型
+use sql_tool_kit::{
+    DeleteAttributeMacro, FieldsAttributeMacro, GenDelete, GenFields, GenInsert, GenSelect, GenSet,
+    GenValues, GenWhere, InsertAttributeMacro, SelectAttributeMacro, SetAttributeMacro,
+    ValuesAttributeMacro, WhereAttributeMacro,
+};
+
+#[derive(GenFields, GenSelect, GenValues, GenInsert)]
+#[config(table = "advert", database = "postgres", conflict = ["id"])]
+pub struct PgInsert {
+    pub id: i32,
+    #[value(conflict_update)]
+    pub title: String,
+}
+
+#[derive(GenWhere, GenSet, GenDelete)]
+#[config(table = "advert", database = "postgres")]
+pub struct PgFilter {
+    #[set(r#where)]
+    #[r#where()]
+    pub id: i32,
+    #[set()]
+    #[r#where(like = "contains")]
+    pub title: Option<String>,
+    #[r#where(in)]
+    pub tags: Vec<String>,
+    #[r#where(range)]
+    pub price: Option<std::ops::Range<i32>>,
+    #[set()]
+    #[r#where()]
+    pub description: Option<Option<String>>,
+}
+
+#[derive(GenWhere, GenSet)]
+#[config(table = "advert", database = "sqlite")]
+pub struct SqliteFilter {
+    #[set(r#where)]
+    #[r#where()]
+    pub id: i64,
+    #[set()]
+    #[r#where(like = "prefix")]
+    pub title: Option<String>,
+    #[r#where(range)]
+    pub price: (Option<i64>, Option<i64>),
+}
+
+#[test]
+fn import_test() {
+    assert_eq!(vec!["id", "title"], PgInsert::generate_fields_clause());
+    assert_eq!(vec!["id", "title"], PgInsert::generate_select_clause());
+    assert_eq!(vec!["$1", "$2"], PgInsert::generate_values_clause());
+    assert_eq!(
+        Ok("INSERT INTO advert (id, title) VALUES ($1, $2) \
+            ON CONFLICT (id) DO UPDATE SET title = EXCLUDED.title"
+            .to_string()),
+        PgInsert::generate_upsert_sql()
+    );
+
+    let filter = PgFilter {
+        id: 1,
+        title: Some("标题".to_string()),
+        tags: vec!["rust".to_string()],
+        price: Some(1..10),
+        description: Some(None),
+    };
+    let (where_data, args) = filter.generate_where_clause_with_args();
+    assert_eq!(
+        vec![
+            "id = $1",
+            "title LIKE $2 ESCAPE '!'",
+            "tags IN ($3)",
+            "price >= $4 AND price < $5",
+            "description IS NULL"
+        ],
+        where_data
+    );
+    assert_eq!(5, args.len());
+    assert_eq!(
+        vec!["title = $1", "description = NULL"],
+        filter.generate_set_clause()
+    );
+    assert!(filter.generate_delete_sql().is_ok());
+
+    let filter = SqliteFilter {
+        id: 1,
+        title: None,
+        price: (Some(1), None),
+    };
+    assert_eq!(vec!["id = ?", "price >= ?"], filter.generate_where_clause());
+}
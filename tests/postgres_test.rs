@@ -0,0 +1,98 @@
+#![cfg(feature = "postgres")]
+
+use sql_tool_kit::*;
+
+#[derive(GenValues)]
+#[config(database = "postgres")]
+pub struct PgValues {
+    pub title: String,
+    #[value(index = 1)]
+    pub subtitle: String,
+    pub description: String,
+    #[value(value = "now()")]
+    pub created_at: (),
+}
+
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct PgFilter {
+    #[r#where(index = 3)]
+    pub id: i64,
+    #[r#where()]
+    pub title: Option<String>,
+    #[r#where()]
+    pub status: i32,
+}
+
+#[derive(GenSet)]
+#[config(table = "advert", database = "postgres")]
+pub struct PgUpdate {
+    #[set(r#where)]
+    pub id: i64,
+    #[set()]
+    pub title: Option<String>,
+    #[set()]
+    pub status: Option<i32>,
+}
+
+fn debug_params(params: Vec<PgParam<'_>>) -> Vec<String> {
+    params.iter().map(|param| format!("{:?}", param)).collect()
+}
+
+#[test]
+fn pg_params_test() {
+    // `subtitle` 重复引用 `$1`，只返回一次；`created_at` 使用 `value` 替换，不会出现在参数中
+    let value = PgValues {
+        title: "标题".to_string(),
+        subtitle: "副标题".to_string(),
+        description: "描述".to_string(),
+        created_at: (),
+    };
+    assert_eq!(
+        vec!["$1", "$1", "$2", "now()"],
+        PgValues::generate_values_clause()
+    );
+    assert_eq!(
+        vec!["\"标题\"", "\"描述\""],
+        debug_params(value.to_pg_params())
+    );
+
+    // 按照占位符的序号排列，`id` 虽然在最前面，但使用的是 `$3`
+    let filter = PgFilter {
+        id: 7,
+        title: Some("标题".to_string()),
+        status: 1,
+    };
+    assert_eq!(
+        vec!["id = $3", "title = $1", "status = $2"],
+        filter.generate_where_clause()
+    );
+    assert_eq!(
+        vec!["Some(\"标题\")", "1", "7"],
+        debug_params(filter.to_pg_params())
+    );
+    // `title` 为 `None` 时在运行时重新编号，`id` 仍然排在 `status` 之后
+    let filter = PgFilter {
+        id: 7,
+        title: None,
+        status: 1,
+    };
+    assert_eq!(
+        vec!["id = $2", "status = $1"],
+        filter.generate_where_clause()
+    );
+    assert_eq!(vec!["1", "7"], debug_params(filter.to_pg_params()));
+    assert_eq!(3, filter.next_param_index());
+
+    // 先返回 `SET` 部分，再返回 `WHERE` 部分
+    let update = PgUpdate {
+        id: 7,
+        title: None,
+        status: Some(2),
+    };
+    assert_eq!(
+        "UPDATE advert SET status = $1 WHERE id = $2",
+        update.generate_update_sql().unwrap()
+    );
+    assert_eq!(vec!["Some(2)", "7"], debug_params(update.to_pg_params()));
+}
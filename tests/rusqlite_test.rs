@@ -0,0 +1,198 @@
+#![cfg(feature = "rusqlite")]
+
+use rusqlite::Connection;
+use sql_tool_kit::*;
+
+#[derive(GenInsert, GenValues)]
+#[config(table = "advert", database = "sqlite")]
+pub struct SqliteInsert {
+    pub id: i64,
+    pub title: String,
+    #[value(value = "'默认描述'")]
+    pub description: String,
+}
+
+#[derive(GenWhere)]
+#[config(database = "sqlite")]
+pub struct SqliteFilter {
+    #[r#where(condition = ">=")]
+    pub id: Option<i64>,
+    #[r#where(
+        condition_all = "(title = {index} OR {index} IS NULL)",
+        ignore_none = false
+    )]
+    pub title: Option<String>,
+    #[r#where(ignore)]
+    pub page: i64,
+}
+
+#[derive(GenWhere)]
+#[config(database = "sqlite")]
+pub struct SqliteLikeFilter {
+    #[r#where(like = "contains")]
+    pub title: String,
+    #[r#where(like = "prefix", case_insensitive)]
+    pub description: Option<String>,
+}
+
+#[derive(GenWhere, GenSet)]
+#[config(table = "advert", database = "sqlite")]
+pub struct SqliteNullable {
+    #[set(r#where)]
+    #[r#where(ignore)]
+    pub id: i64,
+    #[set()]
+    #[r#where()]
+    pub description: Option<Option<String>>,
+}
+
+#[derive(GenSet)]
+#[config(table = "advert", database = "sqlite")]
+pub struct SqliteUpdate {
+    #[set(r#where)]
+    pub id: i64,
+    #[set()]
+    pub title: Option<String>,
+    #[set(value = "'已更新'", ignore_none = false)]
+    pub description: Option<String>,
+}
+
+fn query_titles(conn: &Connection, filter: &SqliteFilter) -> Vec<(String, String)> {
+    let sql = format!(
+        "SELECT title, description FROM advert WHERE {} ORDER BY id",
+        filter.generate_where_clause().join(" AND ")
+    );
+    let mut stmt = conn.prepare(&sql).unwrap();
+    let rows = stmt
+        .query_map(filter.to_rusqlite_params().as_slice(), |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    rows.map(Result::unwrap).collect()
+}
+
+#[test]
+fn rusqlite_test() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute(
+        "CREATE TABLE advert (id INTEGER PRIMARY KEY, title TEXT, description TEXT)",
+        [],
+    )
+    .unwrap();
+
+    let sql = SqliteInsert::generate_insert_sql();
+    for (id, title) in [(1, "第一条"), (2, "第二条")] {
+        let value = SqliteInsert {
+            id,
+            title: title.to_string(),
+            description: String::new(),
+        };
+        // `description` 使用 `value` 替换，不会出现在参数中
+        assert_eq!(2, value.to_rusqlite_params().len());
+        conn.execute(&sql, value.to_rusqlite_params().as_slice())
+            .unwrap();
+    }
+
+    let update = SqliteUpdate {
+        id: 2,
+        title: Some("第二条（修改）".to_string()),
+        description: None,
+    };
+    let sql = update.generate_update_sql().unwrap();
+    assert_eq!(
+        1,
+        conn.execute(&sql, update.to_rusqlite_params().as_slice())
+            .unwrap()
+    );
+
+    // `id` 被 `ignore_none` 忽略，`title` 的两个 `?` 都会对应一个参数
+    let filter = SqliteFilter {
+        id: None,
+        title: None,
+        page: 1,
+    };
+    assert_eq!(2, filter.to_rusqlite_params().len());
+    assert_eq!(
+        vec![
+            ("第一条".to_string(), "默认描述".to_string()),
+            ("第二条（修改）".to_string(), "已更新".to_string()),
+        ],
+        query_titles(&conn, &filter)
+    );
+
+    let filter = SqliteFilter {
+        id: Some(2),
+        title: Some("第二条（修改）".to_string()),
+        page: 1,
+    };
+    assert_eq!(3, filter.to_rusqlite_params().len());
+    assert_eq!(
+        vec![("第二条（修改）".to_string(), "已更新".to_string())],
+        query_titles(&conn, &filter)
+    );
+
+    // `like` 的值在绑定时转义并加上 `%`，`%` 只按照字面值匹配
+    conn.execute(
+        "INSERT INTO advert (id, title, description) VALUES (3, '折扣 50%', 'ABC')",
+        [],
+    )
+    .unwrap();
+    let like_ids = |filter: &SqliteLikeFilter| -> Vec<i64> {
+        let sql = format!(
+            "SELECT id FROM advert WHERE {} ORDER BY id",
+            filter.generate_where_clause().join(" AND ")
+        );
+        let mut stmt = conn.prepare(&sql).unwrap();
+        let rows = stmt
+            .query_map(filter.to_rusqlite_params().as_slice(), |row| row.get(0))
+            .unwrap();
+        rows.map(Result::unwrap).collect()
+    };
+    let filter = SqliteLikeFilter {
+        title: "条".to_string(),
+        description: None,
+    };
+    assert_eq!(vec![1, 2], like_ids(&filter));
+    let filter = SqliteLikeFilter {
+        title: "0%".to_string(),
+        description: Some("ab".to_string()),
+    };
+    assert_eq!(vec![3], like_ids(&filter));
+    let filter = SqliteLikeFilter {
+        title: "%".to_string(),
+        description: Some("b".to_string()),
+    };
+    assert!(like_ids(&filter).is_empty());
+
+    // `Some(None)` 更新为 `NULL`，查询时生成 `IS NULL`
+    let update = SqliteNullable {
+        id: 3,
+        description: Some(None),
+    };
+    let sql = update.generate_update_sql().unwrap();
+    // `description` 为 `Some(None)` 时不会占用占位符，只绑定 `id`
+    assert_eq!(1, RusqliteSetParams::to_rusqlite_params(&update).len());
+    conn.execute(
+        &sql,
+        RusqliteSetParams::to_rusqlite_params(&update).as_slice(),
+    )
+    .unwrap();
+    let count = |filter: &SqliteNullable| -> i64 {
+        let sql = format!(
+            "SELECT count(*) FROM advert WHERE {}",
+            filter.generate_where_clause().join(" AND ")
+        );
+        conn.query_row(
+            &sql,
+            RusqliteWhereParams::to_rusqlite_params(filter).as_slice(),
+            |row| row.get(0),
+        )
+        .unwrap()
+    };
+    assert_eq!(1, count(&update));
+    let filter = SqliteNullable {
+        id: 0,
+        description: Some(Some("已更新".to_string())),
+    };
+    assert_eq!(1, count(&filter));
+}
@@ -0,0 +1,254 @@
+#![cfg(feature = "sqlx")]
+
+use sql_tool_kit::*;
+use sqlx::{Arguments, Connection, Execute, Row, SqliteConnection};
+
+#[derive(GenInsert, GenValues)]
+#[config(table = "advert", database = "sqlite")]
+pub struct SqliteInsert {
+    pub id: i64,
+    pub title: String,
+    #[value(value = "'默认描述'")]
+    pub description: String,
+}
+
+#[derive(GenWhere)]
+#[config(database = "sqlite")]
+pub struct SqliteFilter {
+    #[r#where(condition = ">=")]
+    pub id: Option<i64>,
+    #[r#where(
+        condition_all = "(title = {index} OR {index} IS NULL)",
+        ignore_none = false
+    )]
+    pub title: Option<String>,
+    #[r#where(ignore)]
+    pub page: i64,
+}
+
+#[derive(GenWhere)]
+#[config(database = "sqlite")]
+pub struct SqliteInFilter {
+    #[r#where(in, rename = "id")]
+    pub ids: Vec<i64>,
+    #[r#where(condition = "<>")]
+    pub title: String,
+}
+
+#[derive(GenWhere)]
+#[config(database = "sqlite")]
+pub struct SqliteRangeFilter {
+    #[r#where(range, rename = "id")]
+    pub ids: (Option<i64>, Option<i64>),
+    #[r#where(condition = "<>")]
+    pub title: String,
+}
+
+#[derive(GenSet)]
+#[config(table = "advert", database = "sqlite")]
+pub struct SqliteUpdate {
+    #[set(r#where)]
+    pub id: i64,
+    #[set()]
+    pub title: Option<String>,
+    #[set(value = "'已更新'", ignore_none = false)]
+    pub description: Option<String>,
+}
+
+#[derive(GenValues)]
+#[config(database = "postgres")]
+pub struct PgValues {
+    pub title: String,
+    #[value(index = 1)]
+    pub subtitle: String,
+    pub description: String,
+}
+
+#[derive(GenWhere)]
+#[config(database = "postgres")]
+pub struct PgFilter {
+    #[r#where(index = 3)]
+    pub id: i64,
+    #[r#where()]
+    pub title: Option<String>,
+    #[r#where()]
+    pub status: i32,
+}
+
+/// 取出查询绑定的参数数量
+fn args_len(mut query: SqlxQuery<'_, sqlx::Postgres>) -> usize {
+    query.take_arguments().unwrap().unwrap().len()
+}
+
+#[tokio::test]
+async fn sqlx_sqlite_test() {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+    sqlx::query("CREATE TABLE advert (id INTEGER PRIMARY KEY, title TEXT, description TEXT)")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let sql = SqliteInsert::generate_insert_sql();
+    for (id, title) in [(1, "第一条"), (2, "第二条")] {
+        let value = SqliteInsert {
+            id,
+            title: title.to_string(),
+            description: String::new(),
+        };
+        value
+            .bind_values_args(sqlx::query(&sql))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+
+    let update = SqliteUpdate {
+        id: 2,
+        title: Some("第二条（修改）".to_string()),
+        description: None,
+    };
+    let sql = update.generate_update_sql().unwrap();
+    update
+        .bind_set_args(sqlx::query(&sql))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    // `id` 被 `ignore_none` 忽略，`title` 的两个 `?` 都会绑定
+    let filter = SqliteFilter {
+        id: None,
+        title: None,
+        page: 1,
+    };
+    let sql = format!(
+        "SELECT title, description FROM advert WHERE {} ORDER BY id",
+        filter.generate_where_clause().join(" AND ")
+    );
+    let rows = filter
+        .bind_where_args(sqlx::query(&sql))
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+    let rows: Vec<(String, String)> = rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+    assert_eq!(
+        vec![
+            ("第一条".to_string(), "默认描述".to_string()),
+            ("第二条（修改）".to_string(), "已更新".to_string()),
+        ],
+        rows
+    );
+
+    let filter = SqliteFilter {
+        id: Some(2),
+        title: Some("第二条（修改）".to_string()),
+        page: 1,
+    };
+    let sql = format!(
+        "SELECT count(*) FROM advert WHERE {}",
+        filter.generate_where_clause().join(" AND ")
+    );
+    let count: i64 = filter
+        .bind_where_args(sqlx::query(&sql))
+        .fetch_one(&mut conn)
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(1, count);
+
+    // 列表的每个元素依次绑定，之后的字段继续绑定
+    let filter = SqliteInFilter {
+        ids: vec![1, 2, 3],
+        title: "第一条".to_string(),
+    };
+    let sql = format!(
+        "SELECT id FROM advert WHERE {}",
+        filter.generate_where_clause().join(" AND ")
+    );
+    let ids: Vec<i64> = filter
+        .bind_where_args(sqlx::query(&sql))
+        .fetch_all(&mut conn)
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(vec![2], ids);
+
+    // 只绑定存在的边界，之后的字段继续绑定
+    for (bounds, title, expected) in [
+        ((Some(1), Some(2)), "第一条", vec![2]),
+        ((None, Some(1)), "", vec![1]),
+        ((None, None), "第一条", vec![2]),
+    ] {
+        let filter = SqliteRangeFilter {
+            ids: bounds,
+            title: title.to_string(),
+        };
+        let sql = format!(
+            "SELECT id FROM advert WHERE {} ORDER BY id",
+            filter.generate_where_clause().join(" AND ")
+        );
+        let ids: Vec<i64> = filter
+            .bind_where_args(sqlx::query(&sql))
+            .fetch_all(&mut conn)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        assert_eq!(expected, ids);
+    }
+}
+
+#[test]
+fn sqlx_postgres_args_test() {
+    // `subtitle` 重复引用 `$1`，只绑定一次
+    let value = PgValues {
+        title: "标题".to_string(),
+        subtitle: "副标题".to_string(),
+        description: "描述".to_string(),
+    };
+    assert_eq!(vec!["$1", "$1", "$2"], PgValues::generate_values_clause());
+    assert_eq!(
+        &[(1, "title"), (2, "description")],
+        PgValues::placeholder_map()
+    );
+    let sql = format!(
+        "INSERT INTO advert (title, subtitle, description) VALUES ({})",
+        PgValues::generate_values_clause().join(", ")
+    );
+    assert_eq!(2, args_len(value.bind_values_args(sqlx::query(&sql))));
+
+    // 按照占位符的序号绑定，先绑定 `title`、`status`，再绑定 `id`，
+    // `bind_where_args` 与 `generate_where_clause_with_args` 按照相同的顺序取得字段
+    let filter = PgFilter {
+        id: 1,
+        title: Some("标题".to_string()),
+        status: 0,
+    };
+    let (where_data, args) = filter.generate_where_clause_with_args();
+    assert_eq!(vec!["id = $3", "title = $1", "status = $2"], where_data);
+    assert_eq!(3, args.len());
+    assert_eq!(
+        args[0].downcast_ref::<Option<String>>(),
+        Some(&Some("标题".to_string()))
+    );
+    assert_eq!(args[1].downcast_ref::<i32>(), Some(&0));
+    assert_eq!(args[2].downcast_ref::<i64>(), Some(&1));
+    let sql = format!("SELECT * FROM advert WHERE {}", where_data.join(" AND "));
+    assert_eq!(3, args_len(filter.bind_where_args(sqlx::query(&sql))));
+
+    // `title` 为 `None` 时在运行时重新编号，`status` 使用 `$1`，`id` 使用 `$2`
+    let filter = PgFilter {
+        id: 1,
+        title: None,
+        status: 0,
+    };
+    let (where_data, args) = filter.generate_where_clause_with_args();
+    assert_eq!(vec!["id = $2", "status = $1"], where_data);
+    assert_eq!(2, args.len());
+    assert_eq!(args[0].downcast_ref::<i32>(), Some(&0));
+    assert_eq!(args[1].downcast_ref::<i64>(), Some(&1));
+    let sql = format!("SELECT * FROM advert WHERE {}", where_data.join(" AND "));
+    assert_eq!(2, args_len(filter.bind_where_args(sqlx::query(&sql))));
+}